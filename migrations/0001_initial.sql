@@ -27,6 +27,35 @@ CREATE TABLE IF NOT EXISTS scrap.session (
 	team INTEGER NOT NULL REFERENCES scrap.team ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS scrap.solve (
+	team INTEGER NOT NULL REFERENCES scrap.team ON DELETE CASCADE,
+	challenge INTEGER NOT NULL REFERENCES scrap.challenge ON DELETE CASCADE,
+	time TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+	PRIMARY KEY (team, challenge)
+);
+
+CREATE TABLE IF NOT EXISTS scrap.award (
+	id SERIAL PRIMARY KEY,
+	team INTEGER NOT NULL REFERENCES scrap.team ON DELETE CASCADE,
+	points INTEGER NOT NULL,
+	reason TEXT NOT NULL,
+	time TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS scrap.announcement (
+	id SERIAL PRIMARY KEY,
+	title TEXT NOT NULL UNIQUE,
+	body TEXT NOT NULL,
+	time TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS scrap.webhook (
+	id SERIAL PRIMARY KEY,
+	url TEXT NOT NULL,
+	events TEXT[] NOT NULL,
+	template TEXT NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS scrap.ctf (
 	id INTEGER NOT NULL UNIQUE CHECK (id = 1) DEFAULT 1,
 	title TEXT NOT NULL,
@@ -35,9 +64,17 @@ CREATE TABLE IF NOT EXISTS scrap.ctf (
 	stop TIMESTAMP WITH TIME ZONE
 );
 
+ALTER TABLE scrap.challenge ADD COLUMN IF NOT EXISTS archived BOOLEAN DEFAULT false;
+ALTER TABLE scrap.team ADD COLUMN IF NOT EXISTS admin BOOLEAN DEFAULT false;
+ALTER TABLE scrap.team ADD COLUMN IF NOT EXISTS notice INTEGER DEFAULT 0;
+ALTER TABLE scrap.team ADD COLUMN IF NOT EXISTS affiliation TEXT;
+ALTER TABLE scrap.team ADD COLUMN IF NOT EXISTS token TEXT UNIQUE;
+
 CREATE INDEX IF NOT EXISTS team_name_hash_index ON scrap.team (name, hash);
 CREATE INDEX IF NOT EXISTS team_score_submit_index ON scrap.team (score DESC, submit ASC) INCLUDE (name, solves);
 CREATE INDEX IF NOT EXISTS session_cookie_index ON scrap.session (cookie);
+CREATE INDEX IF NOT EXISTS award_team_index ON scrap.award (team);
+CREATE INDEX IF NOT EXISTS solve_time_index ON scrap.solve (time);
 
 CREATE OR REPLACE FUNCTION lookup(TEXT) RETURNS INTEGER AS $$
 SELECT team FROM scrap.session WHERE cookie=$1 LIMIT 1;
@@ -54,3 +91,10 @@ $$ LANGUAGE sql IMMUTABLE;
 CREATE OR REPLACE FUNCTION update(solves BIGINT, id INTEGER) RETURNS BIGINT AS $$
 SELECT solves | (1 << (id - 1));
 $$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION score(team INTEGER, solves BIGINT) RETURNS INTEGER AS $$
+SELECT (COALESCE((SELECT SUM(value(challenge.solves)) FROM scrap.challenge challenge
+	WHERE solved($2, challenge.id)), 0)
+	+ COALESCE((SELECT SUM(award.points) FROM scrap.award award
+	WHERE award.team=$1), 0))::INTEGER;
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,4 @@
+CREATE TABLE scrap.template (
+	name TEXT PRIMARY KEY,
+	body TEXT NOT NULL
+);
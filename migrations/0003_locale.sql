@@ -0,0 +1,2 @@
+ALTER TABLE scrap.ctf ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+ALTER TABLE scrap.ctf ADD COLUMN accept_language BOOLEAN NOT NULL DEFAULT false;
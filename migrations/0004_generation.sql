@@ -0,0 +1,19 @@
+CREATE SEQUENCE scrap.generation;
+
+CREATE FUNCTION advance() RETURNS TRIGGER AS $$
+BEGIN
+	PERFORM nextval('scrap.generation');
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER solve_generation AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON scrap.solve
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance();
+CREATE TRIGGER award_generation AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON scrap.award
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance();
+CREATE TRIGGER team_generation AFTER INSERT OR UPDATE OF name, score, submit OR DELETE OR TRUNCATE ON scrap.team
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance();
+CREATE TRIGGER challenge_generation AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON scrap.challenge
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance();
+CREATE TRIGGER ctf_generation AFTER INSERT OR UPDATE OF start ON scrap.ctf
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance();
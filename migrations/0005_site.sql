@@ -0,0 +1,13 @@
+CREATE SEQUENCE scrap.site;
+
+CREATE FUNCTION refresh() RETURNS TRIGGER AS $$
+BEGIN
+	PERFORM nextval('scrap.site');
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER template_site AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON scrap.template
+	FOR EACH STATEMENT EXECUTE PROCEDURE refresh();
+CREATE TRIGGER ctf_site AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON scrap.ctf
+	FOR EACH STATEMENT EXECUTE PROCEDURE refresh();
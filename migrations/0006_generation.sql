@@ -0,0 +1,44 @@
+DROP TRIGGER solve_generation ON scrap.solve;
+DROP TRIGGER award_generation ON scrap.award;
+DROP TRIGGER team_generation ON scrap.team;
+DROP TRIGGER challenge_generation ON scrap.challenge;
+DROP TRIGGER ctf_generation ON scrap.ctf;
+DROP SEQUENCE scrap.generation;
+
+-- A sequence advances before the change that advanced it commits, so the counters are rows instead,
+-- which are read in the same snapshot as the data they track.
+CREATE TABLE scrap.generation (
+	name TEXT PRIMARY KEY,
+	value BIGINT NOT NULL DEFAULT 0
+);
+INSERT INTO scrap.generation (name) VALUES ('graph');
+
+-- Counters are advanced once per transaction, at commit, after every other lock it takes.
+CREATE OR REPLACE FUNCTION advance() RETURNS TRIGGER AS $$
+BEGIN
+	IF current_setting('scrap.advanced_' || TG_ARGV[0], true) IS DISTINCT FROM 'true' THEN
+		UPDATE scrap.generation SET value=value+1 WHERE name=TG_ARGV[0];
+		PERFORM set_config('scrap.advanced_' || TG_ARGV[0], 'true', true);
+	END IF;
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE CONSTRAINT TRIGGER solve_generation AFTER INSERT OR UPDATE OR DELETE ON scrap.solve
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('graph');
+CREATE CONSTRAINT TRIGGER award_generation AFTER INSERT OR UPDATE OR DELETE ON scrap.award
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('graph');
+CREATE CONSTRAINT TRIGGER team_generation AFTER INSERT OR UPDATE OF name, score, submit OR DELETE ON scrap.team
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('graph');
+CREATE CONSTRAINT TRIGGER challenge_generation AFTER INSERT OR UPDATE OR DELETE ON scrap.challenge
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('graph');
+CREATE CONSTRAINT TRIGGER ctf_generation AFTER INSERT OR UPDATE OF start ON scrap.ctf
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('graph');
+CREATE TRIGGER truncate_generation AFTER TRUNCATE ON scrap.solve
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance('graph');
+CREATE TRIGGER truncate_generation AFTER TRUNCATE ON scrap.award
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance('graph');
+CREATE TRIGGER truncate_generation AFTER TRUNCATE ON scrap.team
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance('graph');
+CREATE TRIGGER truncate_generation AFTER TRUNCATE ON scrap.challenge
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance('graph');
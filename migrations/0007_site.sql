@@ -0,0 +1,15 @@
+DROP TRIGGER template_site ON scrap.template;
+DROP TRIGGER ctf_site ON scrap.ctf;
+DROP FUNCTION refresh();
+DROP SEQUENCE scrap.site;
+
+INSERT INTO scrap.generation (name) VALUES ('site');
+
+CREATE CONSTRAINT TRIGGER template_site AFTER INSERT OR UPDATE OR DELETE ON scrap.template
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('site');
+CREATE CONSTRAINT TRIGGER ctf_site AFTER INSERT OR UPDATE OR DELETE ON scrap.ctf
+	DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE PROCEDURE advance('site');
+CREATE TRIGGER truncate_site AFTER TRUNCATE ON scrap.template
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance('site');
+CREATE TRIGGER truncate_site AFTER TRUNCATE ON scrap.ctf
+	FOR EACH STATEMENT EXECUTE PROCEDURE advance('site');
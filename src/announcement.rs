@@ -0,0 +1,46 @@
+use chrono::{DateTime, FixedOffset};
+use pulldown_cmark::Parser;
+use pulldown_cmark::html::push_html;
+use r2d2_postgres::postgres::{self, Transaction};
+use serde::Deserialize;
+
+use crate::ctf::rfc3339;
+
+#[derive(Debug, Deserialize)]
+pub struct Announcement {
+	title: String,
+	body: String,
+	#[serde(default, deserialize_with = "rfc3339")]
+	time: Option<DateTime<FixedOffset>>,
+}
+
+impl Announcement {
+	pub fn new(title: &str, body: &str) -> Self {
+		let mut announcement = Announcement {
+			title: title.to_string(),
+			body: body.to_string(),
+			time: None,
+		};
+		announcement.render();
+		announcement
+	}
+
+	pub fn render(&mut self) {
+		let parser = Parser::new(&self.body);
+		let mut body = String::new();
+		push_html(&mut body, parser);
+		self.body = body;
+	}
+
+	pub fn push(&self, client: &mut Transaction) -> Result<(), postgres::Error> {
+		client.execute("INSERT INTO scrap.announcement (title, body, time) VALUES ($1, $2, COALESCE($3, NOW()))
+			ON CONFLICT (title) DO UPDATE SET body=$2, time=COALESCE($3, scrap.announcement.time)",
+			&[
+				&self.title,
+				&self.body,
+				&self.time,
+			]
+		)?;
+		Ok(())
+	}
+}
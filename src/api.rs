@@ -0,0 +1,235 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::DateTime;
+use chrono::offset::Utc;
+use serde::{Deserialize, Serialize};
+use warp::{Filter, Rejection};
+use warp::http::{self, StatusCode};
+use warp::reject::custom;
+
+use crate::Client;
+use crate::metrics::Metrics;
+use crate::site::Site;
+use crate::standings::Standings;
+use crate::submission::{self, Outcome};
+use crate::webhook::Webhooks;
+
+#[derive(Serialize)]
+struct Failure {
+	error: &'static str,
+}
+
+#[derive(Serialize)]
+struct Challenge {
+	slug: String,
+	title: String,
+	author: String,
+	description: String,
+	tags: Vec<String>,
+	solves: i32,
+	value: i32,
+	solved: bool,
+}
+
+#[derive(Deserialize)]
+pub struct Submission {
+	slug: String,
+	flag: String,
+}
+
+#[derive(Serialize)]
+struct Verdict {
+	correct: bool,
+}
+
+#[derive(Serialize)]
+struct Team {
+	id: i32,
+	name: String,
+	affiliation: Option<String>,
+	score: i32,
+	place: i64,
+	solved: Vec<String>,
+}
+
+pub type Response = Result<http::Response<String>, http::Error>;
+
+fn respond<T: Serialize>(status: StatusCode, value: &T) -> Response {
+	let (status, body) = match serde_json::to_string(value) {
+		Ok(body) => (status, body),
+		Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, String::new()),
+	};
+	http::Response::builder()
+		.status(status)
+		.header("content-type", "application/json")
+		.body(body)
+}
+
+fn success<T: Serialize>(value: &T) -> Response {
+	respond(StatusCode::OK, value)
+}
+
+pub fn failure(status: StatusCode, error: &'static str) -> Response {
+	respond(status, &Failure { error })
+}
+
+/// Extracts the bearer token from the authorization header, or an empty token matching no team.
+pub fn token() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+	warp::header::optional("authorization")
+		.map(|header: Option<String>| header
+			.filter(|header| header.starts_with("Bearer "))
+			.map(|header| header["Bearer ".len()..].to_string())
+			.unwrap_or_default())
+}
+
+macro_rules! authenticate {
+	($client:ident, $token:ident, $text:ident) => {
+		match $client.query("SELECT id FROM scrap.team WHERE token=$1", &[&$token])
+			.map_err(custom)?.get(0) {
+			Some(team) => team.get::<_, i32>("id"),
+			None => return Ok(failure(StatusCode::UNAUTHORIZED, $text.invalid_token)),
+		}
+	}
+}
+
+fn started(client: &mut Client) -> Result<bool, Rejection> {
+	let now = Utc::now();
+	let ctf = &client.query("SELECT start FROM scrap.ctf", &[]).map_err(custom)?[0];
+	Ok(!ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false))
+}
+
+pub fn challenges(mut client: Client, token: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<Response, Rejection> {
+	let text = Site::load(&site, &mut client).map_err(custom)?.text(&language);
+	let team = authenticate!(client, token, text);
+	if !started(&mut client)? {
+		return Ok(failure(StatusCode::FORBIDDEN, text.challenges_unavailable));
+	}
+	let challenges: Vec<Challenge> = client.query("SELECT
+		slug, title, author, description, tags, challenge.solves,
+		value(challenge.solves) as value,
+		solved(team.solves, challenge.id) AS solved
+		FROM scrap.challenge challenge
+		JOIN scrap.team team ON team.id=$1
+		WHERE enabled=true
+		ORDER BY value ASC, slug ASC",
+		&[&team]).map_err(custom)?
+		.iter()
+		.map(|challenge| Challenge {
+			slug: challenge.get("slug"),
+			title: challenge.get("title"),
+			author: challenge.get("author"),
+			description: challenge.get("description"),
+			tags: challenge.get("tags"),
+			solves: challenge.get("solves"),
+			value: challenge.get("value"),
+			solved: challenge.get("solved"),
+		})
+		.collect();
+	Ok(success(&challenges))
+}
+
+pub fn submit(mut client: Client, token: String, language: Option<String>, site: Arc<RwLock<Site>>, form: Submission, webhooks: Webhooks, metrics: Arc<Metrics>) -> Result<Response, Rejection> {
+	let text = Site::load(&site, &mut client).map_err(custom)?.text(&language);
+	let team = authenticate!(client, token, text);
+	if !submission::open(&mut client).map_err(custom)? {
+		return Ok(failure(StatusCode::FORBIDDEN, text.submission_unavailable));
+	}
+	let outcome = submission::attempt(&mut client, team, &form.slug, &form.flag).map_err(custom)?;
+	submission::announce(&webhooks, &outcome, team, &form.slug);
+	metrics.submission(&outcome);
+	Ok(success(&Verdict { correct: outcome != Outcome::Incorrect }))
+}
+
+pub fn scoreboard(mut client: Client, token: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<Response, Rejection> {
+	let text = Site::load(&site, &mut client).map_err(custom)?.text(&language);
+	authenticate!(client, token, text);
+	if !started(&mut client)? {
+		return Ok(failure(StatusCode::FORBIDDEN, text.scoreboard_unavailable));
+	}
+	Ok(success(&Standings::new(&mut client).map_err(custom)?))
+}
+
+pub fn me(mut client: Client, token: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<Response, Rejection> {
+	let text = Site::load(&site, &mut client).map_err(custom)?.text(&language);
+	let id = authenticate!(client, token, text);
+	let team = &client.query("SELECT name, affiliation, score, place FROM (SELECT
+		id, name, affiliation, score, ROW_NUMBER()
+		OVER (ORDER BY score DESC, submit ASC) AS place FROM scrap.team) team
+		WHERE id=$1",
+		&[&id]).map_err(custom)?[0];
+	let solved = client.query("SELECT slug FROM scrap.challenge challenge
+		JOIN scrap.team team ON team.id=$1
+		WHERE enabled=true AND solved(team.solves, challenge.id)
+		ORDER BY slug ASC",
+		&[&id]).map_err(custom)?
+		.iter()
+		.map(|challenge| challenge.get("slug"))
+		.collect();
+	Ok(success(&Team {
+		id,
+		name: team.get("name"),
+		affiliation: team.get("affiliation"),
+		score: team.get("score"),
+		place: team.get("place"),
+		solved,
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{SystemTime, UNIX_EPOCH};
+
+	use serde_json::Value;
+
+	use super::*;
+	use crate::test_pool;
+
+	fn body(response: Response) -> (StatusCode, Value) {
+		let response = response.unwrap();
+		assert_eq!(response.headers()["content-type"], "application/json");
+		(response.status(), serde_json::from_str(response.body()).unwrap())
+	}
+
+	#[test]
+	fn token_is_read_from_bearer_header() {
+		let filter = token();
+		let bearer = warp::test::request().header("authorization", "Bearer 0123abcd").filter(&filter);
+		assert_eq!(bearer.unwrap(), "0123abcd");
+		let basic = warp::test::request().header("authorization", "Basic 0123abcd").filter(&filter);
+		assert_eq!(basic.unwrap(), "");
+		assert_eq!(warp::test::request().filter(&filter).unwrap(), "");
+	}
+
+	#[test]
+	fn failure_is_json_error() {
+		let (status, value) = body(failure(StatusCode::FORBIDDEN, "Scoreboard is not available."));
+		assert_eq!(status, StatusCode::FORBIDDEN);
+		assert_eq!(value, serde_json::json!({ "error": "Scoreboard is not available." }));
+	}
+
+	#[test]
+	fn me_authenticates_by_token() {
+		let pool = match test_pool() {
+			Some(pool) => pool,
+			None => return,
+		};
+		let suffix = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos().to_string();
+		let name = format!("api-{}", suffix);
+		let token = format!("token-{}", suffix);
+		pool.get().unwrap().execute("INSERT INTO scrap.team (name, email, hash, token) VALUES ($1, $1, '', $2)",
+			&[&name, &token]).unwrap();
+
+		let site = Arc::new(RwLock::new(Site::default()));
+		let (status, value) = body(me(pool.get().unwrap(), token.clone(), None, site.clone()).unwrap());
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(value["name"], name.as_str());
+		assert_eq!(value["score"], 0);
+		assert_eq!(value["solved"], serde_json::json!([]));
+
+		let (status, value) = body(me(pool.get().unwrap(), String::new(), None, site).unwrap());
+		assert_eq!(status, StatusCode::UNAUTHORIZED);
+		assert_eq!(value["error"], "Invalid API token.");
+
+		pool.get().unwrap().execute("DELETE FROM scrap.team WHERE name=$1", &[&name]).unwrap();
+	}
+}
@@ -0,0 +1,43 @@
+use r2d2_postgres::postgres;
+
+use crate::Client;
+
+pub struct Award {
+	pub team: String,
+	pub points: i32,
+	pub reason: String,
+}
+
+impl Award {
+	pub fn push(&self, client: &mut Client) -> Result<bool, postgres::Error> {
+		let mut transaction = client.transaction()?;
+		let rows = transaction.execute("INSERT INTO scrap.award (team, points, reason)
+			SELECT id, $2, $3 FROM scrap.team WHERE name=$1",
+			&[
+				&self.team,
+				&self.points,
+				&self.reason,
+			]
+		)?;
+		transaction.execute("UPDATE scrap.team team
+			SET score=score(team.id, team.solves)
+			WHERE name=$1",
+			&[&self.team])?;
+		transaction.commit()?;
+		Ok(rows > 0)
+	}
+
+	pub fn revoke(id: i32, client: &mut Client) -> Result<bool, postgres::Error> {
+		let mut transaction = client.transaction()?;
+		let teams = transaction.query("DELETE FROM scrap.award WHERE id=$1 RETURNING team", &[&id])?;
+		for team in &teams {
+			let team: i32 = team.get("team");
+			transaction.execute("UPDATE scrap.team team
+				SET score=score(team.id, team.solves)
+				WHERE id=$1",
+				&[&team])?;
+		}
+		transaction.commit()?;
+		Ok(!teams.is_empty())
+	}
+}
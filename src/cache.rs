@@ -0,0 +1,69 @@
+use std::sync::{PoisonError, RwLock};
+
+use r2d2_postgres::postgres::{self, Transaction};
+
+use crate::Client;
+
+/// A value built from the database, kept until its counter in `scrap.generation` advances.
+#[derive(Default)]
+pub struct Cache<T> {
+	key: Option<i64>,
+	value: Option<T>,
+}
+
+impl<T: Clone> Cache<T> {
+	/// Returns the cached value, or builds it if `counter` advanced since.
+	/// The counter is read in the same snapshot as the queries of `build`, so a value is never stored under a
+	/// newer key than the data it was built from, and the lock is not held across queries.
+	pub fn get<E, F>(cache: &RwLock<Cache<T>>, client: &mut Client, counter: &str, build: F) -> Result<T, E>
+	where
+		E: From<postgres::Error>,
+		F: FnOnce(&mut Transaction) -> Result<T, E>,
+	{
+		let mut transaction = client.transaction()?;
+		transaction.simple_query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")?;
+		let key: i64 = transaction.query("SELECT value FROM scrap.generation WHERE name=$1", &[&counter])?[0]
+			.get("value");
+		if let Some(value) = cache.read().unwrap_or_else(PoisonError::into_inner).cached(key) {
+			return Ok(value);
+		}
+		let value = build(&mut transaction)?;
+		transaction.commit()?;
+		cache.write().unwrap_or_else(PoisonError::into_inner).store(key, value.clone());
+		Ok(value)
+	}
+
+	/// Returns the value built last, for when the database cannot be reached.
+	pub fn last(cache: &RwLock<Cache<T>>) -> Option<T> {
+		cache.read().unwrap_or_else(PoisonError::into_inner).value.clone()
+	}
+
+	fn cached(&self, key: i64) -> Option<T> {
+		match self.key {
+			Some(cached) if cached == key => self.value.clone(),
+			_ => None,
+		}
+	}
+
+	fn store(&mut self, key: i64, value: T) {
+		self.key = Some(key);
+		self.value = Some(value);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn value_is_keyed_by_generation() {
+		let mut cache = Cache::default();
+		assert_eq!(cache.cached(1), None);
+		cache.store(1, "first");
+		assert_eq!(cache.cached(1), Some("first"));
+		assert_eq!(cache.cached(2), None);
+		cache.store(2, "second");
+		assert_eq!(cache.cached(1), None);
+		assert_eq!(Cache::last(&RwLock::new(cache)), Some("second"));
+	}
+}
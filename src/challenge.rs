@@ -1,14 +1,16 @@
 use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, ErrorKind::InvalidData};
+use std::io::{self, ErrorKind::InvalidInput};
 use std::path::{Path, PathBuf};
 
 use pulldown_cmark::{Parser, Event::Start, Tag::Link, LinkType::Inline, CowStr::Borrowed};
 use pulldown_cmark::html::push_html;
+use r2d2_postgres::postgres::{self, Transaction};
+use r2d2_postgres::postgres::types::ToSql;
 use serde::Deserialize;
 use tiny_keccak::{Shake, Hasher, Xof};
 
-use crate::ClientPool;
+use crate::error::Error;
 
 #[derive(Deserialize)]
 pub struct Challenge {
@@ -20,18 +22,27 @@ pub struct Challenge {
 	files: Vec<PathBuf>,
 	flag: String,
 	enabled: bool,
+	#[serde(skip)]
+	unresolved: Vec<String>,
+}
+
+fn relative(href: &str) -> bool {
+	!(href.is_empty() || href.starts_with('/') || href.starts_with('#') || href.starts_with('?') ||
+		href.split('/').next().map(|head| head.contains(':')).unwrap_or(false))
 }
 
 impl Challenge {
-	pub fn new(config: &Path, out: &Path) -> io::Result<Self> {
-		let mut challenge: Challenge = fs::read_to_string(&config)
-			.and_then(|string| toml::from_str(&string)
-				.map_err(|err| io::Error::new(InvalidData, err)))?;
+	pub fn new(config: &Path, out: Option<&Path>) -> Result<Self, Error> {
+		let string = fs::read_to_string(&config)
+			.map_err(|err| Error::Read(config.to_path_buf(), err))?;
+		let mut challenge: Challenge = toml::from_str(&string)
+			.map_err(|err| Error::Parse(config.to_path_buf(), err))?;
 
-		let base = config.parent().unwrap();
-		let links: BTreeMap<String, String> = challenge.files.iter()
-			.map(|file| base.join(file))
-			.map(|file| fs::read(&file)
+		let base = config.parent().unwrap_or(Path::new(""));
+		let mut links: BTreeMap<String, String> = BTreeMap::new();
+		for path in &challenge.files {
+			let file = base.join(path);
+			let (name, link) = fs::read(&file)
 				.map(|buffer| {
 					let mut hash = [0; 8];
 					let mut shake = Shake::v256();
@@ -40,22 +51,34 @@ impl Challenge {
 					hex::encode(hash)
 				})
 				.and_then(|hash| {
-					let name = file.file_name().unwrap()
-						.to_str().unwrap().to_string();
-					let mut path = out.join("files").join(&hash);
-					fs::create_dir_all(&path)?;
-					path.push(&name);
-					fs::copy(file, path)?;
+					let name = file.file_name()
+						.and_then(|name| name.to_str())
+						.ok_or(io::Error::new(InvalidInput, "invalid file name"))?
+						.to_string();
+					if let Some(out) = out {
+						let mut path = out.join("files").join(&hash);
+						fs::create_dir_all(&path)?;
+						path.push(&name);
+						fs::copy(&file, path)?;
+					}
 					let link = format!("/static/files/{}/{}", hash, name);
 					Ok((name, link))
-				}))
-			.collect::<io::Result<_>>()?;
+				})
+				.map_err(|err| Error::File(config.to_path_buf(), path.to_path_buf(), err))?;
+			if links.insert(name, link).is_some() {
+				let err = io::Error::new(InvalidInput, "duplicate file name");
+				return Err(Error::File(config.to_path_buf(), path.to_path_buf(), err));
+			}
+		}
 
+		let mut unresolved = Vec::new();
 		let parser = Parser::new(&challenge.description)
 			.map(|event| match event {
 				Start(Link(Inline, Borrowed(mut href), title)) => {
-					if let Some(link) = links.get(href) {
-						href = link;
+					match links.get(href) {
+						Some(link) => href = link,
+						None if relative(href) => unresolved.push(href.to_string()),
+						None => (),
 					}
 					Start(Link(Inline, Borrowed(href), title))
 				},
@@ -65,17 +88,33 @@ impl Challenge {
 		let mut description = String::new();
 		push_html(&mut description, parser);
 		challenge.description = description;
+		challenge.unresolved = unresolved;
 		Ok(challenge)
 	}
 
-	pub fn push(&self, pool: &ClientPool) -> Result<(), postgres::Error> {
-		let mut client = pool.get().unwrap();
-		client.execute(
-		"INSERT INTO scrap.challenge (slug, title, author, description, tags, flag, enabled)
-		VALUES ($1, $2, $3, $4, $5, $6, $7)
-		ON CONFLICT (slug) DO UPDATE
-		SET title=$2, author=$3, description=$4, tags=$5, flag=$6, enabled=$7",
-		&[
+	pub fn slug(&self) -> &str {
+		&self.slug
+	}
+
+	/// Field names and values as stored in `scrap.challenge`, for comparison.
+	pub fn fields(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("title", self.title.clone()),
+			("author", self.author.clone()),
+			("description", self.description.clone()),
+			("tags", self.tags.join(", ")),
+			("flag", self.flag.clone()),
+			("enabled", self.enabled.to_string()),
+		]
+	}
+
+	/// Relative links in the description that do not name a file.
+	pub fn unresolved(&self) -> &[String] {
+		&self.unresolved
+	}
+
+	pub fn push(&self, transaction: &mut Transaction) -> Result<(), postgres::Error> {
+		let params: [&dyn ToSql; 7] = [
 			&self.slug,
 			&self.title,
 			&self.author,
@@ -83,7 +122,42 @@ impl Challenge {
 			&self.tags,
 			&self.flag,
 			&self.enabled,
-		])?;
+		];
+		// Updating before inserting avoids consuming ids from the sequence,
+		// which is never rewound so that ids are not reused.
+		let rows = transaction.execute(
+		"UPDATE scrap.challenge
+		SET title=$2, author=$3, description=$4, tags=$5, flag=$6, enabled=$7, archived=false
+		WHERE slug=$1",
+		&params)?;
+		if rows == 0 {
+			transaction.execute(
+			"INSERT INTO scrap.challenge (slug, title, author, description, tags, flag, enabled)
+			VALUES ($1, $2, $3, $4, $5, $6, $7)",
+			&params)?;
+		}
+		Ok(())
+	}
+
+	/// Archives challenges missing from the repository, or deletes them and
+	/// their solves if `prune` is set.
+	pub fn sweep(transaction: &mut Transaction, prune: bool) -> Result<(), postgres::Error> {
+		if prune {
+			transaction.execute("UPDATE scrap.team
+				SET solves=solves & ~(SELECT COALESCE(BIT_OR(1::BIGINT << (id - 1)), 0)
+				FROM scrap.challenge
+				WHERE enabled IS NULL)",
+				&[])?;
+			transaction.execute("DELETE FROM scrap.challenge WHERE enabled IS NULL", &[])?;
+			transaction.execute("UPDATE scrap.team team
+				SET score=score(team.id, team.solves)",
+				&[])?;
+		} else {
+			transaction.execute("UPDATE scrap.challenge
+				SET enabled=false, archived=true
+				WHERE enabled IS NULL",
+				&[])?;
+		}
 		Ok(())
 	}
 }
@@ -0,0 +1,139 @@
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::ArgMatches;
+use log::LevelFilter;
+use native_tls::{Certificate, Identity, TlsAcceptor, TlsConnector};
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::logger;
+
+const DEFAULT: &str = "scrap.toml";
+
+/// Acceptor built from the certificate and private key for serving HTTPS, with an optional port for redirecting HTTP.
+pub struct Tls {
+	pub acceptor: TlsAcceptor,
+	pub redirect: Option<u16>,
+}
+
+/// Where the server accepts connections.
+pub enum Listen {
+	Tcp(SocketAddr),
+	Unix(PathBuf),
+}
+
+/// Options read from the config file, each of which is overridden by its flag or `SCRAP_*` variable.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+	port: Option<u16>,
+	bind: Option<String>,
+	repo: Option<PathBuf>,
+	#[serde(rename = "static")]
+	static_path: Option<PathBuf>,
+	uri: Option<String>,
+	pool_size: Option<u32>,
+	db_ca: Option<PathBuf>,
+	cert: Option<PathBuf>,
+	key: Option<PathBuf>,
+	redirect: Option<u16>,
+	log_level: Option<String>,
+}
+
+impl Config {
+	/// Reads the config file at `path`, or `scrap.toml` if it exists when no path is given.
+	pub fn new(path: Option<&Path>) -> Result<Self, Error> {
+		let path = match path {
+			Some(path) => path,
+			None if Path::new(DEFAULT).is_file() => Path::new(DEFAULT),
+			None => return Ok(Config::default()),
+		};
+		let string = fs::read_to_string(path)
+			.map_err(|err| Error::Read(path.to_path_buf(), err))?;
+		toml::from_str(&string).map_err(|err| Error::Parse(path.to_path_buf(), err))
+	}
+
+	pub fn uri(&self, matches: &ArgMatches) -> Result<String, Error> {
+		option(matches, "uri", &self.uri)?.ok_or(Error::Missing("uri"))
+	}
+
+	pub fn repo(&self, matches: &ArgMatches) -> Result<PathBuf, Error> {
+		option(matches, "repo", &self.repo)?.ok_or(Error::Missing("repo"))
+	}
+
+	pub fn static_path(&self, matches: &ArgMatches) -> Result<Option<PathBuf>, Error> {
+		option(matches, "static", &self.static_path)
+	}
+
+	/// Reads `bind` as either `unix:` followed by a socket path, or an IPv4 or IPv6 address to listen on at `port`.
+	pub fn listen(&self, matches: &ArgMatches) -> Result<Listen, Error> {
+		let bind = option(matches, "bind", &self.bind)?;
+		let ip = match bind {
+			Some(ref bind) if bind.starts_with("unix:") => return Ok(Listen::Unix(PathBuf::from(&bind["unix:".len() ..]))),
+			Some(bind) => bind.trim_start_matches('[').trim_end_matches(']').parse()
+				.map_err(|_| Error::Option("bind"))?,
+			None => IpAddr::V4(Ipv4Addr::LOCALHOST),
+		};
+		let port = option(matches, "port", &self.port)?.ok_or(Error::Missing("port"))?;
+		Ok(Listen::Tcp(SocketAddr::new(ip, port)))
+	}
+
+	/// Loads the certificate chain and PKCS #8 private key up front, so a bad file fails at startup.
+	pub fn tls(&self, matches: &ArgMatches) -> Result<Option<Tls>, Error> {
+		let cert = option(matches, "cert", &self.cert)?;
+		let key = option(matches, "key", &self.key)?;
+		let redirect = option(matches, "redirect", &self.redirect)?;
+		match (cert, key) {
+			(Some(cert), Some(key)) => {
+				let chain = fs::read(&cert).map_err(|err| Error::Read(cert.clone(), err))?;
+				let private = fs::read(&key).map_err(|err| Error::Read(key.clone(), err))?;
+				let acceptor = Identity::from_pkcs8(&chain, &private)
+					.and_then(TlsAcceptor::new)
+					.map_err(|err| Error::Identity(cert, key, err))?;
+				Ok(Some(Tls { acceptor, redirect }))
+			},
+			(Some(_), None) => Err(Error::Missing("key")),
+			(None, Some(_)) => Err(Error::Missing("cert")),
+			(None, None) if redirect.is_some() => Err(Error::Missing("cert")),
+			(None, None) => Ok(None),
+		}
+	}
+
+	/// Builds the database TLS connector, trusting each certificate in `db-ca` as well as the system roots.
+	pub fn connector(&self, matches: &ArgMatches) -> Result<TlsConnector, Error> {
+		let mut builder = TlsConnector::builder();
+		if let Some(path) = option(matches, "db-ca", &self.db_ca)? {
+			let bundle = fs::read_to_string(&path)
+				.map_err(|err| Error::Read(path.clone(), err))?;
+			let end = "-----END CERTIFICATE-----";
+			for pem in bundle.split_terminator(end).filter(|pem| pem.contains("-----BEGIN")) {
+				let certificate = Certificate::from_pem(format!("{}{}", pem, end).as_bytes())
+					.map_err(|err| Error::Certificate(path.clone(), err))?;
+				builder.add_root_certificate(certificate);
+			}
+		}
+		builder.build().map_err(Error::Tls)
+	}
+
+	pub fn pool_size(&self, matches: &ArgMatches) -> Result<u32, Error> {
+		match option(matches, "pool-size", &self.pool_size)? {
+			Some(0) => Err(Error::Option("pool-size")),
+			size => Ok(size.unwrap_or(10)),
+		}
+	}
+
+	pub fn log_level(&self, matches: &ArgMatches) -> Result<LevelFilter, Error> {
+		let name = option(matches, "log-level", &self.log_level)?.unwrap_or_else(|| "info".to_string());
+		logger::level(&name).ok_or(Error::Option("log-level"))
+	}
+}
+
+fn option<T: FromStr + Clone>(matches: &ArgMatches, name: &'static str, config: &Option<T>) -> Result<Option<T>, Error> {
+	match matches.value_of(name) {
+		Some(value) => value.parse().map(Some).map_err(|_| Error::Option(name)),
+		None => Ok(config.clone()),
+	}
+}
@@ -1,21 +1,25 @@
 use std::fs;
-use std::io::{self, ErrorKind::InvalidData};
 use std::path::Path;
 
 use chrono::{DateTime, FixedOffset};
 use pulldown_cmark::Parser;
 use pulldown_cmark::html::push_html;
-use r2d2_postgres::postgres;
+use r2d2_postgres::postgres::{self, Transaction};
 use serde::{Deserialize, de};
 
-use crate::ClientPool;
+use crate::announcement::Announcement;
+use crate::error::Error;
+use crate::locale::Locale;
+use crate::webhook::Webhook;
 
-fn rfc3339<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+pub fn rfc3339<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
 where
 	D: de::Deserializer<'de>,
 {
 	let datetime: toml::value::Datetime = de::Deserialize::deserialize(deserializer)?;
-	Ok(Some(DateTime::parse_from_rfc3339(&datetime.to_string()).unwrap()))
+	DateTime::parse_from_rfc3339(&datetime.to_string())
+		.map(Some)
+		.map_err(|err| de::Error::custom(format!("invalid date {}: {}", datetime, err)))
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,33 +30,60 @@ pub struct Ctf {
 	start: Option<DateTime<FixedOffset>>,
 	#[serde(default, deserialize_with = "rfc3339")]
 	stop: Option<DateTime<FixedOffset>>,
+	#[serde(default)]
+	locale: Locale,
+	#[serde(default)]
+	accept_language: bool,
+	#[serde(default)]
+	announcements: Vec<Announcement>,
+	#[serde(default)]
+	webhooks: Vec<Webhook>,
 }
 
 impl Ctf {
-	pub fn new(config: &Path) -> io::Result<Self> {
-		let mut ctf: Ctf = fs::read_to_string(&config)
-			.and_then(|string| toml::from_str(&string)
-				.map_err(|err| io::Error::new(InvalidData, err)))?;
+	pub fn new(config: &Path) -> Result<Self, Error> {
+		let string = fs::read_to_string(&config)
+			.map_err(|err| Error::Read(config.to_path_buf(), err))?;
+		let mut ctf: Ctf = toml::from_str(&string)
+			.map_err(|err| Error::Parse(config.to_path_buf(), err))?;
 
 		let parser = Parser::new(&ctf.home);
 		let mut home = String::new();
 		push_html(&mut home, parser);
 		ctf.home = home;
+		for announcement in &mut ctf.announcements {
+			announcement.render();
+		}
 		Ok(ctf)
 	}
 
-	pub fn push(&self, pool: &ClientPool) -> Result<(), postgres::Error> {
-		let mut client = pool.get().unwrap();
-		client.simple_query("SELECT setval(pg_get_serial_sequence('scrap.challenge', 'id'), max(id)) FROM scrap.challenge")?;
-		client.execute("INSERT INTO scrap.ctf (title, home, start, stop) VALUES ($1, $2, $3, $4)
-			ON CONFLICT (id) DO UPDATE SET title=$1, home=$2, start=$3, stop=$4",
+	pub fn start(&self) -> Option<DateTime<FixedOffset>> {
+		self.start
+	}
+
+	pub fn stop(&self) -> Option<DateTime<FixedOffset>> {
+		self.stop
+	}
+
+	pub fn push(&self, transaction: &mut Transaction) -> Result<(), postgres::Error> {
+		transaction.execute("INSERT INTO scrap.ctf (title, home, start, stop, locale, accept_language) VALUES ($1, $2, $3, $4, $5, $6)
+			ON CONFLICT (id) DO UPDATE SET title=$1, home=$2, start=$3, stop=$4, locale=$5, accept_language=$6",
 			&[
 				&self.title,
 				&self.home,
 				&self.start,
 				&self.stop,
+				&self.locale.code(),
+				&self.accept_language,
 			]
 		)?;
+		for announcement in &self.announcements {
+			announcement.push(transaction)?;
+		}
+		transaction.simple_query("DELETE FROM scrap.webhook")?;
+		for webhook in &self.webhooks {
+			webhook.push(transaction)?;
+		}
 		Ok(())
 	}
 }
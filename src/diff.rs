@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::ClientPool;
+use crate::challenge::Challenge;
+use crate::error::Error;
+
+pub fn run(repo: &Path, pool: &ClientPool) -> Result<(), Error> {
+	let mut challenges: BTreeMap<String, Challenge> = BTreeMap::new();
+	for entry in fs::read_dir(repo).map_err(|err| Error::Read(repo.to_path_buf(), err))? {
+		let path = entry.map_err(|err| Error::Read(repo.to_path_buf(), err))?
+			.path().join("challenge.toml");
+		if path.is_file() {
+			let challenge = Challenge::new(&path, None)?;
+			challenges.insert(challenge.slug().to_string(), challenge);
+		}
+	}
+
+	let rows = pool.get()?.query("SELECT slug, title, author, description, tags, flag,
+		COALESCE(enabled, false) AS enabled, solves, archived
+		FROM scrap.challenge
+		ORDER BY slug ASC",
+		&[])?;
+	for row in &rows {
+		let slug: String = row.get("slug");
+		let solves: i32 = row.get("solves");
+		let challenge = match challenges.remove(&slug) {
+			Some(challenge) => challenge,
+			None => {
+				if !row.get::<_, bool>("archived") {
+					println!("- {} (removed, {} solves)", slug, solves);
+				}
+				continue;
+			},
+		};
+		let tags: Vec<String> = row.get("tags");
+		let enabled: bool = row.get("enabled");
+		let changes: Vec<(&str, String, String)> = challenge.fields().into_iter()
+			.map(|(field, new)| {
+				let old = match field {
+					"tags" => tags.join(", "),
+					"enabled" => enabled.to_string(),
+					_ => row.get(field),
+				};
+				(field, old, new)
+			})
+			.filter(|(_, old, new)| old != new)
+			.collect();
+		if changes.is_empty() {
+			continue;
+		}
+		println!("~ {}", slug);
+		for (field, old, new) in changes {
+			println!("    {}: {:?} -> {:?}", field, old, new);
+			if field == "flag" && solves > 0 {
+				println!("    warning: {} solves were made with the old flag", solves);
+			}
+		}
+	}
+	for slug in challenges.keys() {
+		println!("+ {} (added)", slug);
+	}
+	Ok(())
+}
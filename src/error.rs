@@ -0,0 +1,72 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use r2d2_postgres::{postgres, r2d2};
+
+#[derive(Debug)]
+pub enum Error {
+	Read(PathBuf, io::Error),
+	Parse(PathBuf, toml::de::Error),
+	File(PathBuf, PathBuf, io::Error),
+	Listen(PathBuf, io::Error),
+	Bind(SocketAddr, io::Error),
+	Identity(PathBuf, PathBuf, native_tls::Error),
+	Template(tera::Error),
+	Certificate(PathBuf, native_tls::Error),
+	Tls(native_tls::Error),
+	Uri(postgres::Error),
+	Postgres(postgres::Error),
+	Pool(r2d2::Error),
+	Missing(&'static str),
+	Option(&'static str),
+	Message(&'static str),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Read(path, err) => write!(f, "{}: {}", path.display(), err),
+			Error::Parse(path, err) => write!(f, "{}: {}", path.display(), err),
+			Error::File(path, file, err) => write!(f, "{}: file {}: {}", path.display(), file.display(), err),
+			Error::Listen(path, err) => write!(f, "{}: cannot listen: {}", path.display(), err),
+			Error::Bind(address, err) => write!(f, "{}: cannot listen: {}", address, err),
+			Error::Identity(cert, key, err) => write!(f, "{} and {}: invalid certificate or private key: {}",
+				cert.display(), key.display(), err),
+			Error::Certificate(path, err) => write!(f, "{}: invalid certificate: {}", path.display(), err),
+			Error::Tls(err) => write!(f, "database TLS error: {}", err),
+			Error::Template(err) => {
+				write!(f, "template error: {}", err)?;
+				let mut source = error::Error::source(err);
+				while let Some(err) = source {
+					write!(f, ": {}", err)?;
+					source = err.source();
+				}
+				Ok(())
+			},
+			Error::Uri(err) => write!(f, "invalid database URI: {}", err),
+			Error::Postgres(err) => write!(f, "database error: {}", err),
+			Error::Pool(err) => write!(f, "database connection error: {}", err),
+			Error::Missing(name) => write!(f, "missing {}: pass --{}, set SCRAP_{}, or add it to the config file",
+				name, name, name.replace('-', "_").to_uppercase()),
+			Error::Option(name) => write!(f, "invalid value for {}", name),
+			Error::Message(message) => write!(f, "{}", message),
+		}
+	}
+}
+
+impl error::Error for Error {}
+
+impl From<postgres::Error> for Error {
+	fn from(err: postgres::Error) -> Self {
+		Error::Postgres(err)
+	}
+}
+
+impl From<r2d2::Error> for Error {
+	fn from(err: r2d2::Error) -> Self {
+		Error::Pool(err)
+	}
+}
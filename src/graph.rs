@@ -0,0 +1,190 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+
+use chrono::DateTime;
+use chrono::offset::Utc;
+use maud::html;
+use r2d2_postgres::postgres::{self, Transaction};
+
+use crate::Client;
+use crate::cache::Cache;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 300.0;
+const MARGIN: f64 = 40.0;
+const COLORS: [&str; 10] = [
+	"#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd",
+	"#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf",
+];
+
+/// The last drawn graph, keyed by the scoreboard generation it was drawn at.
+pub type Graph = Cache<String>;
+
+struct Line {
+	id: i32,
+	name: String,
+	solved: BTreeSet<i32>,
+	awarded: i32,
+	points: Vec<(DateTime<Utc>, i32)>,
+}
+
+impl Graph {
+	/// Returns the cached graph, redrawing it if the scoreboard changed since.
+	pub fn render(graph: &RwLock<Graph>, client: &mut Client) -> Result<String, postgres::Error> {
+		Cache::get(graph, client, "graph", draw)
+	}
+}
+
+fn draw(client: &mut Transaction) -> Result<String, postgres::Error> {
+	let mut lines: Vec<Line> = client.query("SELECT id, name FROM scrap.team
+		ORDER BY score DESC, submit ASC
+		LIMIT 10",
+		&[])?.iter()
+		.map(|team| Line {
+			id: team.get("id"),
+			name: team.get("name"),
+			solved: BTreeSet::new(),
+			awarded: 0,
+			points: Vec::new(),
+		})
+		.collect();
+	let ids: Vec<i32> = lines.iter().map(|line| line.id).collect();
+
+	// Historical challenge values are looked up through value() so that
+	// the graph always matches the scoring formula in the database.
+	let values: Vec<i32> = client.query("SELECT value(n) AS value
+		FROM generate_series(0, (SELECT COALESCE(MAX(solves), 0) + 1 FROM scrap.challenge)) n
+		ORDER BY n ASC",
+		&[])?.iter()
+		.map(|row| row.get("value"))
+		.collect();
+	let solves = client.query("SELECT team, challenge, NULL::INTEGER AS points, time
+		FROM scrap.solve
+		UNION ALL
+		SELECT team, NULL, points, time
+		FROM scrap.award
+		WHERE team=ANY($1)
+		ORDER BY time ASC",
+		&[&ids])?;
+	let start: Option<DateTime<Utc>> = client.query("SELECT start FROM scrap.ctf", &[])?
+		.get(0)
+		.and_then(|ctf| ctf.get("start"));
+
+	let mut counts: HashMap<i32, usize> = HashMap::new();
+	for event in &solves {
+		let team: i32 = event.get("team");
+		let time: DateTime<Utc> = event.get("time");
+		match event.get::<_, Option<i32>>("challenge") {
+			Some(challenge) => {
+				*counts.entry(challenge).or_insert(0) += 1;
+				if let Some(line) = lines.iter_mut().find(|line| line.id == team) {
+					line.solved.insert(challenge);
+				}
+			},
+			None => if let Some(line) = lines.iter_mut().find(|line| line.id == team) {
+				line.awarded += event.get::<_, Option<i32>>("points").unwrap_or(0);
+			},
+		}
+		for line in &mut lines {
+			let score = line.awarded + line.solved.iter()
+				.map(|challenge| values.get(counts[challenge]).cloned().unwrap_or(0))
+				.sum::<i32>();
+			if line.points.last().map(|&(_, last)| last != score).unwrap_or(score != 0) {
+				line.points.push((time, score));
+			}
+		}
+	}
+
+	let first = start.or_else(|| solves.first().map(|event| event.get("time")));
+	let last = solves.last().map(|event| event.get::<_, DateTime<Utc>>("time"));
+	match (first, last) {
+		(Some(first), Some(last)) if first < last => Ok(plot(&lines, first, last)),
+		_ => Ok(String::new()),
+	}
+}
+
+/// Draws each line as a step plot of its score over time, with a legend below the axes.
+fn plot(lines: &[Line], first: DateTime<Utc>, last: DateTime<Utc>) -> String {
+	let max = lines.iter()
+		.flat_map(|line| line.points.iter().map(|&(_, score)| score))
+		.max()
+		.unwrap_or(0)
+		.max(1);
+	let span = (last - first).num_seconds().max(1) as f64;
+	let x = |time: DateTime<Utc>| MARGIN + (WIDTH - 2.0 * MARGIN) * (time - first).num_seconds() as f64 / span;
+	let y = |score: i32| HEIGHT - MARGIN - (HEIGHT - 2.0 * MARGIN) * score.max(0) as f64 / max as f64;
+
+	let polylines: Vec<String> = lines.iter()
+		.map(|line| {
+			let mut score = 0;
+			let mut points = format!("{:.1},{:.1}", x(first), y(score));
+			for &(time, next) in &line.points {
+				points += &format!(" {:.1},{:.1} {:.1},{:.1}", x(time), y(score), x(time), y(next));
+				score = next;
+			}
+			points + &format!(" {:.1},{:.1}", x(last), y(score))
+		})
+		.collect();
+
+	let height = HEIGHT + 20.0 * lines.len() as f64;
+	let bottom = HEIGHT - MARGIN;
+	let right = WIDTH - MARGIN;
+	let label = MARGIN - 4.0;
+	let date = bottom + 14.0;
+	let legend = MARGIN + 16.0;
+	html! {
+		svg xmlns="http://www.w3.org/2000/svg" viewBox={ "0 0 " (WIDTH) " " (height) } {
+			line x1=(MARGIN) y1=(bottom) x2=(right) y2=(bottom) stroke="currentColor" {}
+			line x1=(MARGIN) y1=(MARGIN) x2=(MARGIN) y2=(bottom) stroke="currentColor" {}
+			text x=(label) y=(MARGIN) text-anchor="end" font-size="10" fill="currentColor" { (max) }
+			text x=(label) y=(bottom) text-anchor="end" font-size="10" fill="currentColor" { "0" }
+			text x=(MARGIN) y=(date) font-size="10" fill="currentColor" {
+				(first.format("%Y-%m-%d %H:%M UTC"))
+			}
+			text x=(right) y=(date) text-anchor="end" font-size="10" fill="currentColor" {
+				(last.format("%Y-%m-%d %H:%M UTC"))
+			}
+			@for (i, (line, points)) in lines.iter().zip(&polylines).enumerate() {
+				@let color = COLORS[i % COLORS.len()];
+				@let offset = HEIGHT + 20.0 * i as f64;
+				@let top = offset - 10.0;
+				polyline points=(points) fill="none" stroke=(color) stroke-width="2" {}
+				rect x=(MARGIN) y=(top) width="10" height="10" fill=(color) {}
+				text x=(legend) y=(offset) font-size="12" fill="currentColor" {
+					(line.name)
+				}
+			}
+		}
+	}.into_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn time(time: &str) -> DateTime<Utc> {
+		time.parse().unwrap()
+	}
+
+	fn line(id: i32, name: &str, points: Vec<(DateTime<Utc>, i32)>) -> Line {
+		Line { id, name: name.to_string(), solved: BTreeSet::new(), awarded: 0, points }
+	}
+
+	#[test]
+	fn plot_scales_lines_and_escapes_names() {
+		let first = time("2020-01-01T00:00:00Z");
+		let middle = time("2020-01-01T12:00:00Z");
+		let last = time("2020-01-02T00:00:00Z");
+		let lines = vec![
+			line(1, "<first>", vec![(middle, 500)]),
+			line(2, "second", vec![(last, 250)]),
+		];
+		let svg = plot(&lines, first, last);
+		assert!(svg.contains("viewBox=\"0 0 800 340\""));
+		assert!(svg.contains("points=\"40.0,260.0 400.0,260.0 400.0,40.0 760.0,40.0\""));
+		assert!(svg.contains("points=\"40.0,260.0 760.0,260.0 760.0,150.0 760.0,150.0\""));
+		assert!(svg.contains("&lt;first&gt;"));
+		assert!(!svg.contains("<first>"));
+		assert!(svg.contains(">500<"));
+	}
+}
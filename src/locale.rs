@@ -0,0 +1,445 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+	#[default]
+	En,
+	Fr,
+	Es,
+}
+
+impl Locale {
+	pub fn new(code: &str) -> Option<Self> {
+		// Only the primary subtag matters, so fr-CA is French.
+		match code.split('-').next().unwrap_or("").to_lowercase().as_str() {
+			"en" => Some(Locale::En),
+			"fr" => Some(Locale::Fr),
+			"es" => Some(Locale::Es),
+			_ => None,
+		}
+	}
+
+	pub fn code(self) -> &'static str {
+		match self {
+			Locale::En => "en",
+			Locale::Fr => "fr",
+			Locale::Es => "es",
+		}
+	}
+
+	/// Picks the supported language with the highest quality in an Accept-Language header.
+	pub fn negotiate(header: &str) -> Option<Self> {
+		let mut languages: Vec<(Locale, f32)> = header.split(',')
+			.filter_map(|language| {
+				let mut parts = language.split(';');
+				let locale = Locale::new(parts.next()?.trim())?;
+				let quality = parts
+					.map(|part| part.trim())
+					.filter(|part| part.starts_with("q="))
+					.filter_map(|part| part[2 ..].parse().ok())
+					.next()
+					.unwrap_or(1.0);
+				Some((locale, quality))
+			})
+			.filter(|&(_, quality)| quality > 0.0)
+			.collect();
+		languages.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+		languages.first().map(|&(locale, _)| locale)
+	}
+
+	pub fn text(self) -> &'static Text {
+		match self {
+			Locale::En => &EN,
+			Locale::Fr => &FR,
+			Locale::Es => &ES,
+		}
+	}
+}
+
+/// Every string shown in the web interface and API.
+#[derive(Serialize)]
+pub struct Text {
+	pub code: &'static str,
+
+	pub home: &'static str,
+	pub challenges: &'static str,
+	pub scoreboard: &'static str,
+	pub notices: &'static str,
+	pub profile: &'static str,
+	pub logout: &'static str,
+	pub login: &'static str,
+	pub register: &'static str,
+	pub registration: &'static str,
+	pub admin: &'static str,
+
+	pub challenges_unavailable: &'static str,
+	pub scoreboard_unavailable: &'static str,
+	pub submission_unavailable: &'static str,
+	pub invalid_token: &'static str,
+	pub points_unit: &'static str,
+	pub solves_unit: &'static str,
+	pub author: &'static str,
+	pub tags: &'static str,
+	pub incorrect_flag: &'static str,
+	pub solved: &'static str,
+	pub flag: &'static str,
+	pub submit: &'static str,
+	pub close: &'static str,
+
+	pub team: &'static str,
+	pub score: &'static str,
+	pub time: &'static str,
+	pub challenge: &'static str,
+	pub points: &'static str,
+	pub solves: &'static str,
+	pub awards: &'static str,
+	pub reason: &'static str,
+	pub title: &'static str,
+	pub announcements: &'static str,
+	pub no_announcements: &'static str,
+
+	pub public_page: &'static str,
+	pub team_name: &'static str,
+	pub email: &'static str,
+	pub affiliation: &'static str,
+	pub affiliation_optional: &'static str,
+	pub password: &'static str,
+	pub current_password: &'static str,
+	pub optional: &'static str,
+	pub save: &'static str,
+	pub api_token: &'static str,
+	pub generate: &'static str,
+	pub not_logged_in: &'static str,
+	pub log_in: &'static str,
+
+	pub revoke: &'static str,
+	pub award: &'static str,
+	pub markdown: &'static str,
+	pub announce: &'static str,
+	pub retract: &'static str,
+
+	pub team_name_required: &'static str,
+	pub email_required: &'static str,
+	pub password_required: &'static str,
+	pub current_password_required: &'static str,
+	pub affiliation_too_long: &'static str,
+	pub invalid_team_name: &'static str,
+	pub team_conflict: &'static str,
+	pub email_conflict: &'static str,
+	pub incorrect_password: &'static str,
+	pub invalid_login: &'static str,
+	pub points_integer: &'static str,
+	pub reason_required: &'static str,
+	pub team_not_found: &'static str,
+	pub title_required: &'static str,
+	pub body_required: &'static str,
+
+	pub bad_request: (&'static str, &'static str),
+	pub unauthorized: (&'static str, &'static str),
+	pub not_found: (&'static str, &'static str),
+	pub method_not_allowed: (&'static str, &'static str),
+	pub too_many_requests: (&'static str, &'static str),
+	pub internal_error: (&'static str, &'static str),
+	pub unavailable: (&'static str, &'static str),
+}
+
+impl Text {
+	/// Title and message for an error page, falling back to the generic client or server error.
+	pub fn error(&self, status: u16) -> (&'static str, &'static str) {
+		match status {
+			401 | 403 => self.unauthorized,
+			404 => self.not_found,
+			405 => self.method_not_allowed,
+			429 => self.too_many_requests,
+			503 => self.unavailable,
+			400 ..= 499 => self.bad_request,
+			_ => self.internal_error,
+		}
+	}
+}
+
+pub static EN: Text = Text {
+	code: "en",
+
+	home: "Home",
+	challenges: "Challenges",
+	scoreboard: "Scoreboard",
+	notices: "Notices",
+	profile: "Profile",
+	logout: "Logout",
+	login: "Login",
+	register: "Register",
+	registration: "Registration",
+	admin: "Admin",
+
+	challenges_unavailable: "Challenges are not available.",
+	scoreboard_unavailable: "Scoreboard is not available.",
+	submission_unavailable: "Flag submission is not available.",
+	invalid_token: "Invalid API token.",
+	points_unit: "points",
+	solves_unit: "solves",
+	author: "Author",
+	tags: "Tags",
+	incorrect_flag: "Incorrect flag.",
+	solved: "Your team has solved this challenge.",
+	flag: "Flag",
+	submit: "Submit",
+	close: "Close",
+
+	team: "Team",
+	score: "Score",
+	time: "Time",
+	challenge: "Challenge",
+	points: "Points",
+	solves: "Solves",
+	awards: "Awards",
+	reason: "Reason",
+	title: "Title",
+	announcements: "Announcements",
+	no_announcements: "There are no announcements.",
+
+	public_page: "View public page",
+	team_name: "Team Name",
+	email: "Email",
+	affiliation: "Affiliation",
+	affiliation_optional: "Affiliation (Optional)",
+	password: "Password",
+	current_password: "Current Password",
+	optional: "Optional",
+	save: "Save",
+	api_token: "API Token",
+	generate: "Generate",
+	not_logged_in: "Log in to view your profile.",
+	log_in: "Log In",
+
+	revoke: "Revoke",
+	award: "Award",
+	markdown: "Markdown/HTML",
+	announce: "Announce",
+	retract: "Retract",
+
+	team_name_required: "Team name is required.",
+	email_required: "Email is required.",
+	password_required: "Password is required.",
+	current_password_required: "Current password is required.",
+	affiliation_too_long: "Affiliation is too long.",
+	invalid_team_name: "Invalid team name length or characters.",
+	team_conflict: "Team name or email conflict.",
+	email_conflict: "Email conflict.",
+	incorrect_password: "Incorrect password.",
+	invalid_login: "Invalid team name or password.",
+	points_integer: "Points must be an integer.",
+	reason_required: "Reason is required.",
+	team_not_found: "Team does not exist.",
+	title_required: "Title is required.",
+	body_required: "Body is required.",
+
+	bad_request: ("Bad Request", "The request could not be understood."),
+	unauthorized: ("Unauthorized", "You are not allowed to view this page."),
+	not_found: ("Page Not Found", "This page does not exist."),
+	method_not_allowed: ("Method Not Allowed", "This page does not accept that kind of request."),
+	too_many_requests: ("Too Many Requests", "Too many requests were made. Try again later."),
+	internal_error: ("Internal Server Error", "Something went wrong. Try again later."),
+	unavailable: ("Service Unavailable", "The server is busy. Try again in a few seconds."),
+};
+
+pub static FR: Text = Text {
+	code: "fr",
+
+	home: "Accueil",
+	challenges: "Épreuves",
+	scoreboard: "Classement",
+	notices: "Annonces",
+	profile: "Profil",
+	logout: "Déconnexion",
+	login: "Connexion",
+	register: "Inscription",
+	registration: "Inscription",
+	admin: "Administration",
+
+	challenges_unavailable: "Les épreuves ne sont pas disponibles.",
+	scoreboard_unavailable: "Le classement n'est pas disponible.",
+	submission_unavailable: "La soumission de drapeaux n'est pas disponible.",
+	invalid_token: "Jeton d'API invalide.",
+	points_unit: "points",
+	solves_unit: "résolutions",
+	author: "Auteur",
+	tags: "Catégories",
+	incorrect_flag: "Flag incorrect.",
+	solved: "Votre équipe a résolu cette épreuve.",
+	flag: "Flag",
+	submit: "Valider",
+	close: "Fermer",
+
+	team: "Équipe",
+	score: "Score",
+	time: "Heure",
+	challenge: "Épreuve",
+	points: "Points",
+	solves: "Résolutions",
+	awards: "Bonus",
+	reason: "Motif",
+	title: "Titre",
+	announcements: "Annonces",
+	no_announcements: "Il n'y a aucune annonce.",
+
+	public_page: "Voir la page publique",
+	team_name: "Nom d'équipe",
+	email: "E-mail",
+	affiliation: "Affiliation",
+	affiliation_optional: "Affiliation (facultatif)",
+	password: "Mot de passe",
+	current_password: "Mot de passe actuel",
+	optional: "Facultatif",
+	save: "Enregistrer",
+	api_token: "Jeton d'API",
+	generate: "Générer",
+	not_logged_in: "Connectez-vous pour voir votre profil.",
+	log_in: "Se connecter",
+
+	revoke: "Révoquer",
+	award: "Attribuer",
+	markdown: "Markdown/HTML",
+	announce: "Publier",
+	retract: "Retirer",
+
+	team_name_required: "Le nom d'équipe est obligatoire.",
+	email_required: "L'e-mail est obligatoire.",
+	password_required: "Le mot de passe est obligatoire.",
+	current_password_required: "Le mot de passe actuel est obligatoire.",
+	affiliation_too_long: "L'affiliation est trop longue.",
+	invalid_team_name: "Longueur ou caractères du nom d'équipe invalides.",
+	team_conflict: "Nom d'équipe ou e-mail déjà utilisé.",
+	email_conflict: "E-mail déjà utilisé.",
+	incorrect_password: "Mot de passe incorrect.",
+	invalid_login: "Nom d'équipe ou mot de passe invalide.",
+	points_integer: "Les points doivent être un nombre entier.",
+	reason_required: "Le motif est obligatoire.",
+	team_not_found: "L'équipe n'existe pas.",
+	title_required: "Le titre est obligatoire.",
+	body_required: "Le contenu est obligatoire.",
+
+	bad_request: ("Requête invalide", "La requête n'a pas pu être comprise."),
+	unauthorized: ("Non autorisé", "Vous n'êtes pas autorisé à voir cette page."),
+	not_found: ("Page introuvable", "Cette page n'existe pas."),
+	method_not_allowed: ("Méthode non autorisée", "Cette page n'accepte pas ce type de requête."),
+	too_many_requests: ("Trop de requêtes", "Trop de requêtes ont été envoyées. Réessayez plus tard."),
+	internal_error: ("Erreur interne du serveur", "Une erreur est survenue. Réessayez plus tard."),
+	unavailable: ("Service indisponible", "Le serveur est surchargé. Réessayez dans quelques secondes."),
+};
+
+pub static ES: Text = Text {
+	code: "es",
+
+	home: "Inicio",
+	challenges: "Retos",
+	scoreboard: "Clasificación",
+	notices: "Avisos",
+	profile: "Perfil",
+	logout: "Cerrar sesión",
+	login: "Iniciar sesión",
+	register: "Registrarse",
+	registration: "Registro",
+	admin: "Administración",
+
+	challenges_unavailable: "Los retos no están disponibles.",
+	scoreboard_unavailable: "La clasificación no está disponible.",
+	submission_unavailable: "El envío de banderas no está disponible.",
+	invalid_token: "Token de API no válido.",
+	points_unit: "puntos",
+	solves_unit: "resoluciones",
+	author: "Autor",
+	tags: "Etiquetas",
+	incorrect_flag: "Flag incorrecta.",
+	solved: "Tu equipo ha resuelto este reto.",
+	flag: "Flag",
+	submit: "Enviar",
+	close: "Cerrar",
+
+	team: "Equipo",
+	score: "Puntuación",
+	time: "Hora",
+	challenge: "Reto",
+	points: "Puntos",
+	solves: "Resoluciones",
+	awards: "Bonificaciones",
+	reason: "Motivo",
+	title: "Título",
+	announcements: "Avisos",
+	no_announcements: "No hay avisos.",
+
+	public_page: "Ver página pública",
+	team_name: "Nombre del equipo",
+	email: "Correo electrónico",
+	affiliation: "Afiliación",
+	affiliation_optional: "Afiliación (opcional)",
+	password: "Contraseña",
+	current_password: "Contraseña actual",
+	optional: "Opcional",
+	save: "Guardar",
+	api_token: "Token de API",
+	generate: "Generar",
+	not_logged_in: "Inicia sesión para ver tu perfil.",
+	log_in: "Iniciar sesión",
+
+	revoke: "Revocar",
+	award: "Otorgar",
+	markdown: "Markdown/HTML",
+	announce: "Publicar",
+	retract: "Retirar",
+
+	team_name_required: "El nombre del equipo es obligatorio.",
+	email_required: "El correo electrónico es obligatorio.",
+	password_required: "La contraseña es obligatoria.",
+	current_password_required: "La contraseña actual es obligatoria.",
+	affiliation_too_long: "La afiliación es demasiado larga.",
+	invalid_team_name: "Longitud o caracteres del nombre del equipo no válidos.",
+	team_conflict: "El nombre del equipo o el correo electrónico ya está en uso.",
+	email_conflict: "El correo electrónico ya está en uso.",
+	incorrect_password: "Contraseña incorrecta.",
+	invalid_login: "Nombre del equipo o contraseña no válidos.",
+	points_integer: "Los puntos deben ser un número entero.",
+	reason_required: "El motivo es obligatorio.",
+	team_not_found: "El equipo no existe.",
+	title_required: "El título es obligatorio.",
+	body_required: "El contenido es obligatorio.",
+
+	bad_request: ("Solicitud incorrecta", "No se pudo entender la solicitud."),
+	unauthorized: ("No autorizado", "No tienes permiso para ver esta página."),
+	not_found: ("Página no encontrada", "Esta página no existe."),
+	method_not_allowed: ("Método no permitido", "Esta página no acepta ese tipo de solicitud."),
+	too_many_requests: ("Demasiadas solicitudes", "Se han enviado demasiadas solicitudes. Inténtalo más tarde."),
+	internal_error: ("Error interno del servidor", "Algo salió mal. Inténtalo más tarde."),
+	unavailable: ("Servicio no disponible", "El servidor está ocupado. Inténtalo en unos segundos."),
+};
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn new_reads_primary_subtag() {
+		assert_eq!(Locale::new("fr-CA"), Some(Locale::Fr));
+		assert_eq!(Locale::new("ES"), Some(Locale::Es));
+		assert_eq!(Locale::new("de"), None);
+	}
+
+	#[test]
+	fn negotiate_prefers_highest_quality() {
+		assert_eq!(Locale::negotiate("de, es;q=0.8, fr;q=0.9"), Some(Locale::Fr));
+		assert_eq!(Locale::negotiate("en;q=0.1, es"), Some(Locale::Es));
+		assert_eq!(Locale::negotiate("fr;q=0, de"), None);
+		assert_eq!(Locale::negotiate("*"), None);
+		assert_eq!(Locale::negotiate(""), None);
+	}
+
+	#[test]
+	fn error_falls_back_by_class() {
+		assert_eq!(EN.error(418), EN.bad_request);
+		assert_eq!(EN.error(502), EN.internal_error);
+		assert_eq!(FR.error(404), FR.not_found);
+	}
+}
@@ -0,0 +1,53 @@
+use std::io::{self, Write};
+
+use chrono::offset::Utc;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+struct Logger;
+
+impl Log for Logger {
+	fn enabled(&self, metadata: &Metadata) -> bool {
+		metadata.level() <= log::max_level()
+	}
+
+	fn log(&self, record: &Record) {
+		if !self.enabled(record.metadata()) {
+			return;
+		}
+		let stderr = io::stderr();
+		let mut stderr = stderr.lock();
+		let _ = match record.target() {
+			target if target.starts_with("scrap") => writeln!(stderr, "time={} level={} {}",
+				Utc::now().to_rfc3339(), record.level().to_string().to_lowercase(), record.args()),
+			target => writeln!(stderr, "time={} level={} target={} msg={}",
+				Utc::now().to_rfc3339(), record.level().to_string().to_lowercase(),
+				quote(target), quote(&record.args().to_string())),
+		};
+	}
+
+	fn flush(&self) {
+		let _ = io::stderr().flush();
+	}
+}
+
+pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
+	log::set_boxed_logger(Box::new(Logger))?;
+	log::set_max_level(level);
+	Ok(())
+}
+
+pub fn level(name: &str) -> Option<LevelFilter> {
+	match name {
+		"off" => Some(LevelFilter::Off),
+		_ => name.parse::<Level>().ok().map(|level| level.to_level_filter()),
+	}
+}
+
+/// Formats a value for logfmt, quoting it if necessary.
+pub fn quote(value: &str) -> String {
+	if !value.is_empty() && value.chars().all(|c| c.is_ascii_graphic() && c != '"' && c != '=') {
+		value.to_string()
+	} else {
+		format!("{:?}", value)
+	}
+}
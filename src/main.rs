@@ -1,69 +1,290 @@
 #![feature(proc_macro_hygiene)]
+#![recursion_limit = "256"]
 
+mod announcement;
+mod api;
+mod award;
+mod cache;
 mod challenge;
+mod config;
 mod ctf;
+mod diff;
+mod error;
+mod graph;
+mod locale;
+mod logger;
+mod metrics;
+mod migration;
 mod server;
+mod site;
+mod standings;
+mod submission;
+mod template;
+mod validate;
+mod webhook;
 
-use std::fs;
 use std::path::Path;
+use std::process;
 
-use clap::{App, Arg, value_t};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand, value_t};
+use postgres_native_tls::MakeTlsConnector;
 use r2d2_postgres::PostgresConnectionManager;
-use r2d2_postgres::postgres::NoTls;
 use r2d2_postgres::r2d2::{Pool, PooledConnection};
 
+use crate::award::Award;
 use crate::challenge::Challenge;
-use crate::ctf::Ctf;
+use crate::config::Config;
+use crate::error::Error;
+use crate::standings::Standings;
 
-type ClientPool = Pool<PostgresConnectionManager<NoTls>>;
-type Client = PooledConnection<PostgresConnectionManager<NoTls>>;
+type ClientPool = Pool<PostgresConnectionManager<MakeTlsConnector>>;
+type Client = PooledConnection<PostgresConnectionManager<MakeTlsConnector>>;
 
 fn main() {
 	let matches = App::new("scrap").version("1.0")
-		.arg(Arg::with_name("port")
-			.long("port")
-			.help("Server port")
+		.setting(AppSettings::SubcommandRequiredElseHelp)
+		.arg(Arg::with_name("config")
+			.long("config")
+			.help("Config file, scrap.toml by default")
 			.takes_value(true)
-			.required(true))
-		.arg(Arg::with_name("repo")
-			.long("repo")
-			.help("Repository directory")
+			.env("SCRAP_CONFIG")
+			.global(true))
+		.arg(Arg::with_name("log-level")
+			.long("log-level")
+			.help("Log level, info by default")
 			.takes_value(true)
-			.required(true))
-		.arg(Arg::with_name("static")
-			.long("static")
-			.help("Static directory")
+			.possible_values(&["off", "error", "warn", "info", "debug", "trace"])
+			.env("SCRAP_LOG_LEVEL")
+			.global(true))
+		.arg(Arg::with_name("db-ca")
+			.long("db-ca")
+			.help("CA bundle PEM file trusted for database TLS, in addition to the system roots")
 			.takes_value(true)
-			.required(true))
-		.arg(Arg::with_name("uri")
-			.long("uri")
-			.help("PostgreSQL database URI")
-			.takes_value(true)
-			.required(true))
+			.env("SCRAP_DB_CA")
+			.global(true))
+		.subcommand(SubCommand::with_name("serve")
+			.about("Serves the CTF")
+			.arg(Arg::with_name("port")
+				.long("port")
+				.help("Server port")
+				.takes_value(true)
+				.env("SCRAP_PORT"))
+			.arg(Arg::with_name("bind")
+				.long("bind")
+				.help("Server IP address or unix:<socket path>, 127.0.0.1 by default")
+				.takes_value(true)
+				.env("SCRAP_BIND"))
+			.arg(Arg::with_name("cert")
+				.long("cert")
+				.help("TLS certificate chain PEM file, which enables HTTPS")
+				.takes_value(true)
+				.env("SCRAP_CERT"))
+			.arg(Arg::with_name("key")
+				.long("key")
+				.help("TLS private key PEM file")
+				.takes_value(true)
+				.env("SCRAP_KEY"))
+			.arg(Arg::with_name("redirect")
+				.long("redirect")
+				.help("Port redirecting HTTP to HTTPS")
+				.takes_value(true)
+				.env("SCRAP_REDIRECT"))
+			.arg(Arg::with_name("static")
+				.long("static")
+				.help("Static directory to serve at /static, if not served separately")
+				.takes_value(true)
+				.env("SCRAP_STATIC"))
+			.arg(Arg::with_name("pool-size")
+				.long("pool-size")
+				.help("Maximum database connections, 10 by default")
+				.takes_value(true)
+				.env("SCRAP_POOL_SIZE"))
+			.arg(uri()))
+		.subcommand(SubCommand::with_name("migrate")
+			.about("Creates or updates the database schema")
+			.arg(uri()))
+		.subcommand(SubCommand::with_name("import")
+			.about("Imports a repository into the database")
+			.arg(repo())
+			.arg(Arg::with_name("static")
+				.long("static")
+				.help("Static directory")
+				.takes_value(true)
+				.env("SCRAP_STATIC"))
+			.arg(uri())
+			.arg(Arg::with_name("prune")
+				.long("prune")
+				.help("Deletes challenges missing from the repository instead of archiving them")))
+		.subcommand(SubCommand::with_name("validate")
+			.about("Checks a repository without importing it")
+			.arg(repo()))
+		.subcommand(SubCommand::with_name("diff")
+			.about("Shows how importing a repository would change challenges")
+			.arg(repo())
+			.arg(uri()))
+		.subcommand(SubCommand::with_name("export")
+			.about("Prints the scoreboard as CTFtime JSON")
+			.arg(uri()))
+		.subcommand(SubCommand::with_name("admin")
+			.about("Manages teams and awards")
+			.setting(AppSettings::SubcommandRequiredElseHelp)
+			.arg(uri())
+			.subcommand(SubCommand::with_name("award")
+				.about("Awards or deducts points")
+				.arg(Arg::with_name("team")
+					.long("team")
+					.help("Team name")
+					.takes_value(true)
+					.required(true))
+				.arg(Arg::with_name("points")
+					.long("points")
+					.help("Points, negative for a penalty")
+					.takes_value(true)
+					.allow_hyphen_values(true)
+					.required(true))
+				.arg(Arg::with_name("reason")
+					.long("reason")
+					.help("Reason text")
+					.takes_value(true)
+					.required(true)))
+			.subcommand(SubCommand::with_name("revoke")
+				.about("Revokes an award")
+				.arg(Arg::with_name("id")
+					.long("id")
+					.help("Award identifier")
+					.takes_value(true)
+					.required(true)))
+			.subcommand(SubCommand::with_name("promote")
+				.about("Grants a team access to the admin page")
+				.arg(Arg::with_name("team")
+					.long("team")
+					.help("Team name")
+					.takes_value(true)
+					.required(true))))
 		.get_matches();
 
-	let port = value_t!(matches.value_of("port"), u16).unwrap();
-	let uri = matches.value_of("uri").unwrap();
-	let repo_path = Path::new(matches.value_of("repo").unwrap());
-	let static_path = Path::new(matches.value_of("static").unwrap());
-
-	let manager = PostgresConnectionManager::new(uri.parse().unwrap(), NoTls);
-	let pool = Pool::new(manager).unwrap();
-	let schema = include_str!("../scrap.sql");
-	pool.get().unwrap().simple_query(schema).unwrap();
-
-	let ctf = Ctf::new(&repo_path.join("ctf.toml")).unwrap();
-	ctf.push(&pool).unwrap();
-
-	pool.get().unwrap().simple_query("UPDATE scrap.challenge SET enabled=NULL").unwrap();
-	for challenge in fs::read_dir(repo_path).unwrap()
-		.filter_map(|entry| entry.ok())
-		.map(|entry| entry.path().join("challenge.toml"))
-		.filter(|path| path.is_file())
-		.map(|path| Challenge::new(&path, &static_path).unwrap()) {
-			challenge.push(&pool).unwrap();
-		}
-	pool.get().unwrap().simple_query("DELETE FROM scrap.challenge WHERE enabled IS NULL").unwrap();
-
-	server::run(port, pool);
+	let (name, matches) = matches.subcommand();
+	let matches = matches.unwrap();
+	let result = Config::new(matches.value_of("config").map(Path::new))
+		.and_then(|config| run(name, matches, &config));
+	if let Err(e) = result {
+		eprintln!("error: {}", e);
+		process::exit(1);
+	}
+}
+
+fn run(name: &str, matches: &ArgMatches, config: &Config) -> Result<(), Error> {
+	logger::init(config.log_level(matches)?).unwrap();
+
+	match name {
+		"serve" => serve(matches, config),
+		"migrate" => migrate(matches, config),
+		"import" => import(matches, config),
+		"validate" => validate::run(&config.repo(matches)?),
+		"diff" => diff::run(&config.repo(matches)?, &connect(matches, config)?),
+		"export" => export(matches, config),
+		"admin" => admin(matches, config),
+		_ => unreachable!(),
+	}
+}
+
+fn uri<'a, 'b>() -> Arg<'a, 'b> {
+	Arg::with_name("uri")
+		.long("uri")
+		.help("PostgreSQL database URI")
+		.takes_value(true)
+		.env("SCRAP_URI")
+		.hide_env_values(true)
+}
+
+fn repo<'a, 'b>() -> Arg<'a, 'b> {
+	Arg::with_name("repo")
+		.long("repo")
+		.help("Repository directory")
+		.takes_value(true)
+		.env("SCRAP_REPO")
+}
+
+fn connect(matches: &ArgMatches, config: &Config) -> Result<ClientPool, Error> {
+	// Whether TLS is used is decided by sslmode in the URI.
+	let tls = MakeTlsConnector::new(config.connector(matches)?);
+	let manager = PostgresConnectionManager::new(config.uri(matches)?.parse().map_err(Error::Uri)?, tls);
+	Ok(Pool::builder().max_size(config.pool_size(matches)?).build(manager)?)
+}
+
+/// Connects to the database in SCRAP_TEST_URI, if set, which is migrated and may be written to.
+#[cfg(test)]
+fn test_pool() -> Option<ClientPool> {
+	let uri = std::env::var("SCRAP_TEST_URI").ok()?;
+	let manager = PostgresConnectionManager::new(uri.parse().unwrap(),
+		MakeTlsConnector::new(native_tls::TlsConnector::new().unwrap()));
+	let pool = Pool::builder().max_size(2).build(manager).unwrap();
+	migration::run(&mut pool.get().unwrap()).unwrap();
+	Some(pool)
+}
+
+fn serve(matches: &ArgMatches, config: &Config) -> Result<(), Error> {
+	let listen = config.listen(matches)?;
+	let tls = config.tls(matches)?;
+	let static_path = config.static_path(matches)?;
+	let pool = connect(matches, config)?;
+	server::run(listen, tls, static_path, pool)
+}
+
+fn migrate(matches: &ArgMatches, config: &Config) -> Result<(), Error> {
+	let pool = connect(matches, config)?;
+	migration::run(&mut pool.get()?)?;
+	Ok(())
+}
+
+fn import(matches: &ArgMatches, config: &Config) -> Result<(), Error> {
+	let repo_path = config.repo(matches)?;
+	let static_path = config.static_path(matches)?.ok_or(Error::Missing("static"))?;
+	let repository = validate::parse(&repo_path, Some(&static_path))?;
+	let pool = connect(matches, config)?;
+
+	// Everything is applied in one transaction, so a failed import changes nothing.
+	let mut client = pool.get()?;
+	let mut transaction = client.transaction()?;
+	repository.ctf.push(&mut transaction)?;
+	repository.templates.push(&mut transaction)?;
+	// Challenges still NULL after the upserts are missing from the repository.
+	transaction.simple_query("UPDATE scrap.challenge SET enabled=NULL")?;
+	for challenge in &repository.challenges {
+		challenge.push(&mut transaction)?;
+	}
+	Challenge::sweep(&mut transaction, matches.is_present("prune"))?;
+	transaction.commit()?;
+	Ok(())
+}
+
+fn export(matches: &ArgMatches, config: &Config) -> Result<(), Error> {
+	let pool = connect(matches, config)?;
+	let standings = Standings::new(&mut pool.get()?)?;
+	println!("{}", serde_json::to_string_pretty(&standings).unwrap());
+	Ok(())
+}
+
+fn admin(matches: &ArgMatches, config: &Config) -> Result<(), Error> {
+	let pool = connect(matches, config)?;
+	let mut client = pool.get()?;
+
+	let found = match matches.subcommand() {
+		("award", Some(matches)) => Award {
+			team: matches.value_of("team").unwrap().to_string(),
+			points: value_t!(matches.value_of("points"), i32).unwrap_or_else(|e| e.exit()),
+			reason: matches.value_of("reason").unwrap().to_string(),
+		}.push(&mut client)?,
+		("revoke", Some(matches)) => {
+			let id = value_t!(matches.value_of("id"), i32).unwrap_or_else(|e| e.exit());
+			Award::revoke(id, &mut client)?
+		},
+		("promote", Some(matches)) => client.execute("UPDATE scrap.team SET admin=true WHERE name=$1",
+			&[&matches.value_of("team").unwrap()])? > 0,
+		_ => unreachable!(),
+	};
+	if !found {
+		return Err(Error::Message("No matching team or award."));
+	}
+	Ok(())
 }
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use log::warn;
+use r2d2_postgres::postgres;
+
+use crate::{Client, ClientPool};
+use crate::logger::quote;
+use crate::submission::Outcome;
+
+const BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+const ROUTES: [&str; 23] = [
+	"/", "/challenges", "/scoreboard", "/scoreboard.json", "/profile", "/profile/token",
+	"/register", "/login", "/logout", "/notices", "/team/:id", "/admin", "/admin/award",
+	"/admin/revoke", "/admin/announce", "/admin/retract", "/api/v1/challenges",
+	"/api/v1/submit", "/api/v1/scoreboard", "/api/v1/me", "/metrics", "/healthz",
+	"/readyz",
+];
+
+#[derive(Default)]
+struct Histogram {
+	buckets: [u64; 11],
+	sum: f64,
+	count: u64,
+}
+
+impl Histogram {
+	fn observe(&mut self, duration: Duration) {
+		let seconds = duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1e9;
+		for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS.iter()) {
+			if seconds <= *bound {
+				*bucket += 1;
+			}
+		}
+		self.sum += seconds;
+		self.count += 1;
+	}
+
+	fn write(&self, out: &mut String, name: &str, labels: &str) {
+		let separator = if labels.is_empty() { "" } else { "," };
+		for (bucket, bound) in self.buckets.iter().zip(BUCKETS.iter()) {
+			writeln!(out, "{}_bucket{{{}{}le=\"{}\"}} {}", name, labels, separator, bound, bucket).unwrap();
+		}
+		writeln!(out, "{}_bucket{{{}{}le=\"+Inf\"}} {}", name, labels, separator, self.count).unwrap();
+		writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum).unwrap();
+		writeln!(out, "{}_count{{{}}} {}", name, labels, self.count).unwrap();
+	}
+}
+
+#[derive(Default)]
+pub struct Metrics {
+	requests: Mutex<BTreeMap<(&'static str, String, u16), Histogram>>,
+	waits: Mutex<Histogram>,
+	incorrect: AtomicU64,
+	solved: AtomicU64,
+	first_blood: AtomicU64,
+}
+
+fn escape(value: &str) -> String {
+	value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn route(path: &str) -> &'static str {
+	let path = path.trim_end_matches('/');
+	let segments: Vec<&str> = path.split('/').collect();
+	match segments.as_slice() {
+		["", "team", id] if id.parse::<i32>().is_ok() => "/team/:id",
+		[""] => "/",
+		_ => ROUTES.iter().find(|&&route| route == path).cloned().unwrap_or("other"),
+	}
+}
+
+impl Metrics {
+	pub fn request(&self, path: &str, method: &str, status: u16, elapsed: Duration) {
+		self.requests.lock().unwrap()
+			.entry((route(path), method.to_string(), status))
+			.or_default()
+			.observe(elapsed);
+	}
+
+	pub fn wait(&self, elapsed: Duration) {
+		self.waits.lock().unwrap().observe(elapsed);
+	}
+
+	pub fn submission(&self, outcome: &Outcome) {
+		let counter = match outcome {
+			Outcome::Incorrect => &self.incorrect,
+			Outcome::Solved => &self.solved,
+			Outcome::FirstBlood => &self.first_blood,
+		};
+		counter.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Renders every metric, where those read from the database are left out if no connection is free,
+	/// so that the in-process counters are still served while the pool is saturated.
+	pub fn render(&self, pool: &ClientPool) -> String {
+		let mut out = String::new();
+
+		out += "# HELP scrap_http_request_duration_seconds HTTP request latency by route.\n";
+		out += "# TYPE scrap_http_request_duration_seconds histogram\n";
+		for ((route, method, status), histogram) in self.requests.lock().unwrap().iter() {
+			let labels = format!("route=\"{}\",method=\"{}\",status=\"{}\"", route, method, status);
+			histogram.write(&mut out, "scrap_http_request_duration_seconds", &labels);
+		}
+
+		out += "# HELP scrap_submissions_total Flag submissions by outcome.\n";
+		out += "# TYPE scrap_submissions_total counter\n";
+		for (outcome, count) in &[
+			("incorrect", &self.incorrect),
+			("solved", &self.solved),
+			("first_blood", &self.first_blood),
+		] {
+			writeln!(out, "scrap_submissions_total{{outcome=\"{}\"}} {}", outcome, count.load(Ordering::Relaxed)).unwrap();
+		}
+
+		if let Some(mut client) = pool.try_get() {
+			match gauges(&mut client) {
+				Ok(gauges) => out += &gauges,
+				Err(err) => warn!("event=metrics error={}", quote(&err.to_string())),
+			}
+		}
+
+		let state = pool.state();
+		out += "# HELP scrap_pool_connections Database pool connections by state.\n";
+		out += "# TYPE scrap_pool_connections gauge\n";
+		writeln!(out, "scrap_pool_connections{{state=\"idle\"}} {}", state.idle_connections).unwrap();
+		writeln!(out, "scrap_pool_connections{{state=\"in_use\"}} {}", state.connections - state.idle_connections).unwrap();
+		out += "# HELP scrap_pool_wait_seconds Time spent waiting for a database connection.\n";
+		out += "# TYPE scrap_pool_wait_seconds histogram\n";
+		self.waits.lock().unwrap().write(&mut out, "scrap_pool_wait_seconds", "");
+
+		out
+	}
+}
+
+fn gauges(client: &mut Client) -> Result<String, postgres::Error> {
+	let mut out = String::new();
+	let counts = &client.query("SELECT
+		(SELECT COUNT(*) FROM scrap.session) AS sessions,
+		(SELECT COUNT(*) FROM scrap.team) AS teams",
+		&[])?[0];
+	let sessions: i64 = counts.get("sessions");
+	let teams: i64 = counts.get("teams");
+	out += "# HELP scrap_sessions Active sessions.\n";
+	out += "# TYPE scrap_sessions gauge\n";
+	writeln!(out, "scrap_sessions {}", sessions).unwrap();
+	out += "# HELP scrap_teams Registered teams.\n";
+	out += "# TYPE scrap_teams gauge\n";
+	writeln!(out, "scrap_teams {}", teams).unwrap();
+
+	out += "# HELP scrap_challenge_solves Solves per enabled challenge.\n";
+	out += "# TYPE scrap_challenge_solves gauge\n";
+	for challenge in &client.query("SELECT slug, solves FROM scrap.challenge
+		WHERE enabled=true
+		ORDER BY slug ASC", &[])? {
+		let slug: String = challenge.get("slug");
+		let solves: i32 = challenge.get("solves");
+		writeln!(out, "scrap_challenge_solves{{challenge=\"{}\"}} {}", escape(&slug), solves).unwrap();
+	}
+
+	Ok(out)
+}
@@ -0,0 +1,38 @@
+use log::info;
+use r2d2_postgres::postgres;
+
+use crate::Client;
+
+/// Migrations in order, where the version of each is its position starting from 1.
+const MIGRATIONS: &[&str] = &[
+	include_str!("../migrations/0001_initial.sql"),
+	include_str!("../migrations/0002_template.sql"),
+	include_str!("../migrations/0003_locale.sql"),
+	include_str!("../migrations/0004_generation.sql"),
+	include_str!("../migrations/0005_site.sql"),
+	include_str!("../migrations/0006_generation.sql"),
+	include_str!("../migrations/0007_site.sql"),
+];
+
+/// Applies every migration newer than the database's schema version, in a single transaction.
+pub fn run(client: &mut Client) -> Result<usize, postgres::Error> {
+	let mut transaction = client.transaction()?;
+	transaction.simple_query("CREATE SCHEMA IF NOT EXISTS scrap;
+		CREATE TABLE IF NOT EXISTS scrap.schema_version (
+			version INTEGER PRIMARY KEY,
+			time TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+		);
+		LOCK TABLE scrap.schema_version IN EXCLUSIVE MODE")?;
+	let current: i32 = transaction.query("SELECT COALESCE(MAX(version), 0) AS version FROM scrap.schema_version", &[])?[0]
+		.get("version");
+	let mut applied = 0;
+	for (index, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+		let version = index as i32 + 1;
+		transaction.simple_query(migration)?;
+		transaction.execute("INSERT INTO scrap.schema_version (version) VALUES ($1)", &[&version])?;
+		info!("event=migrate version={}", version);
+		applied += 1;
+	}
+	transaction.commit()?;
+	Ok(applied)
+}
@@ -1,16 +1,57 @@
 use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 use chrono::DateTime;
 use chrono::offset::Utc;
 use maud::{html, DOCTYPE, Markup, PreEscaped};
+use log::{error, info, warn};
+use r2d2_postgres::{postgres, r2d2};
 use r2d2_postgres::postgres::error::SqlState;
 use r2d2_postgres::postgres::row::Row;
+use serde_json::{json, Value};
+use tera::Context;
+use tokio::net::TcpListener;
+use tokio::prelude::{Future, FutureExt, Stream};
+use tokio::prelude::future::{self, Either};
+use tokio::timer::Delay;
+use tokio_tls::TlsAcceptor;
+use tokio_uds::UnixListener;
 use warp::{any, body, reply, Filter, Reply, Rejection};
-use warp::http::{Response, StatusCode};
+use warp::http::{HeaderValue, Response, StatusCode};
 use warp::reject::custom;
 use warp::reply::with_header;
-use warp::path::{end, path};
+use warp::path::{end, full, param, path, peek, FullPath, Peek};
 
 use crate::{Client, ClientPool};
+use crate::announcement::Announcement;
+use crate::award::Award;
+use crate::config::{Listen, Tls};
+use crate::error::Error;
+use crate::graph::Graph;
+use crate::locale::Text;
+use crate::logger::quote;
+use crate::metrics::Metrics;
+use crate::site::{Settings, Site};
+use crate::standings::Standings;
+use crate::{api, submission};
+use crate::submission::Outcome;
+use crate::webhook::{Event, Webhooks};
+
+/// TLS handshakes in progress at once, each abandoned after the timeout.
+const HANDSHAKES: usize = 64;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long accepting pauses after a failed accept, such as when out of file descriptors.
+const ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How long a request waits for a database connection before it is answered with a 503.
+const CHECKOUT_TIMEOUT: Duration = Duration::from_secs(5);
 
 macro_rules! result {
 	($expr:expr) => {
@@ -22,75 +63,130 @@ macro_rules! result {
 }
 
 macro_rules! form {
-	($field:expr, $title:expr, $error:expr, $page:ident, $client:ident, $session:ident) => {
+	($field:expr, $title:expr, $error:expr, $page:ident, $text:ident, $settings:ident, $client:ident, $session:ident) => {
 		match $field {
 			Some(value) if value.len() > 0 => value,
 			_ => return Ok(Response::builder()
 				.status(StatusCode::BAD_REQUEST)
-				.body(make_body($title, $page(Some($error)), $client, $session)?))
+				.body(make_body($text, &$settings, $title, $page($text, &$settings, Some($error))?, $client, $session)?))
 		}
 	}	
 }
 
-fn make_body(page: &str, content: Markup, mut client: Client, session: String) -> Result<String, Rejection> {
+fn make_body(text: &Text, settings: &Settings, page: &str, content: Markup, mut client: Client, session: String) -> Result<String, Rejection> {
 	let count: i64 = result!(client.query("SELECT COUNT(*) as count FROM scrap.session
 		WHERE cookie=$1",
 		&[&session]))[0].get("count");
-	let title: String = result!(client.query("SELECT title FROM scrap.ctf", &[]))[0].get(0);
+	let unread: i64 = result!(client.query("SELECT COUNT(*) as count FROM scrap.announcement
+		WHERE id > (SELECT notice FROM scrap.team WHERE id=lookup($1))",
+		&[&session]))[0].get("count");
+	layout(text, settings, page, content, count > 0, unread)
+}
+
+/// Wraps a page in `layout.html` if the repository has one, or in the built-in layout otherwise.
+fn layout(text: &Text, settings: &Settings, page: &str, content: Markup, logged_in: bool, unread: i64) -> Result<String, Rejection> {
+	let title = &settings.title;
+	let content = content.into_string();
+	let mut context = Context::new();
+	context.insert("locale", text.code);
+	context.insert("page", page);
+	context.insert("title", title);
+	context.insert("content", &content);
+	context.insert("logged_in", &logged_in);
+	context.insert("unread", &unread);
+	context.insert("text", text);
+	if let Some(body) = result!(settings.render("layout.html", &context)) {
+		return Ok(body);
+	}
 	Ok(html! {
 		(DOCTYPE)
-		html {
+		html lang=(text.code) {
 			head {
 				meta charset="utf-8";
 				meta name="viewport" content="width=device-width, initial-scale=1";
-				title { @if page.len() > 0 { (page) " | " } (title) }
+				title { (page) @if !page.is_empty() && !title.is_empty() { " | " } (title) }
 				link rel="stylesheet" href="/static/style.css";
 				link rel="icon" type="image/png" href="/static/favicon.png";
 			}
 			body {
 				nav {
 					ul {
-						li { a href="/" { "Home" } }
-						li { a href="/challenges" { "Challenges" } }
-						li { a href="/scoreboard" { "Scoreboard" } }
-						@if count > 0 {
-							li { a href="/profile" { "Profile" } }
-							li { a href="/logout" { "Logout" } }
+						li { a href="/" { (text.home) } }
+						li { a href="/challenges" { (text.challenges) } }
+						li { a href="/scoreboard" { (text.scoreboard) } }
+						li { a href="/notices" {
+							(text.notices)
+							@if unread > 0 { " " span class="unread" { (unread) } }
+						} }
+						@if logged_in {
+							li { a href="/profile" { (text.profile) } }
+							li { a href="/logout" { (text.logout) } }
 						} @else {
-							li { a href="/login" { "Login" } }
-							li { a href="/register" { "Register" } }
+							li { a href="/login" { (text.login) } }
+							li { a href="/register" { (text.register) } }
 						}
 					}
 				}
-				main { (content) }
+				main { (PreEscaped(content)) }
 			}
 		}
 	}.into_string())
 }
 
+/// Draws the content of a page with the repository's `<name>.html` if it has one, or with `markup` otherwise.
+/// The template gets the interface text as `text` alongside the values that `values` inserts.
+fn content<V, M>(text: &Text, settings: &Settings, name: &str, values: V, markup: M) -> Result<Markup, Rejection>
+where
+	V: FnOnce(&mut Context),
+	M: FnOnce() -> Markup,
+{
+	let name = format!("{}.html", name);
+	if !settings.overrides(&name) {
+		return Ok(markup());
+	}
+	let mut context = Context::new();
+	context.insert("locale", text.code);
+	context.insert("text", text);
+	values(&mut context);
+	Ok(PreEscaped(result!(settings.render(&name, &context)).unwrap_or_default()))
+}
+
 fn make_reply(body: String) -> impl Reply {
 	reply::with_header(reply::html(body), "content-security-policy", "script-src 'none'")
 }
 
-fn page(title: &str, content: Markup, client: Client, session: String) -> Result<impl Reply, Rejection> {
-	Ok(make_reply(make_body(title, content, client, session)?))
+fn page(text: &Text, settings: &Settings, title: &str, content: Markup, client: Client, session: String) -> Result<impl Reply, Rejection> {
+	Ok(make_reply(make_body(text, settings, title, content, client, session)?))
 }
 
-fn get_home(mut client: Client, session: String) -> Result<impl Reply, Rejection> {
+fn get_home(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
 	let home: String = result!(client.query("SELECT home FROM scrap.ctf", &[]))[0].get("home");
-	Ok(page("", html! {
-		(PreEscaped(home))
-	}, client, session)?)
+	let content = content(text, &settings, "home", |context| {
+		context.insert("home", &home);
+	}, || html! {
+		(PreEscaped(&home))
+	})?;
+	Ok(page(text, &settings, "", content, client, session)?)
 }
 
-fn get_challenges(mut client: Client, session: String, invalid: String) -> Result<impl Reply, Rejection> {
+fn get_challenges(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, invalid: String) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
 	let now = Utc::now();
 	let ctf = &result!(client.query("SELECT start, stop FROM scrap.ctf", &[]))[0];
 	if ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false) {
-		return Ok(with_header(page("Challenges", html! {
-			h1 { "Challenges" }
-			p { "Challenges are not available." }
-		}, client, session)?, "set-cookie", "invalid=; HttpOnly; SameSite=Lax; Max-Age=-1"));
+		let content = content(text, &settings, "challenges", |context| {
+			context.insert("available", &false);
+			context.insert("invalid", &invalid);
+			context.insert("challenges", &Vec::<Value>::new());
+		}, || html! {
+			h1 { (text.challenges) }
+			p { (text.challenges_unavailable) }
+		})?;
+		return Ok(with_header(page(text, &settings, text.challenges, content, client, session)?,
+			"set-cookie", "invalid=; HttpOnly; SameSite=Lax; Max-Age=-1"));
 	}
 	let challenges = result!(client.query("SELECT
 		slug, title, author, description, tags, challenge.solves,
@@ -102,9 +198,23 @@ fn get_challenges(mut client: Client, session: String, invalid: String) -> Resul
 		WHERE enabled=true
 		ORDER BY value ASC, slug ASC",
 		&[&session]));
-	Ok(with_header(page("Challenges", html! {
+	let content = content(text, &settings, "challenges", |context| {
+		context.insert("available", &true);
+		context.insert("invalid", &invalid);
+		context.insert("challenges", &challenges.iter().map(|challenge| json!({
+			"slug": challenge.get::<_, String>("slug"),
+			"title": challenge.get::<_, String>("title"),
+			"author": challenge.get::<_, String>("author"),
+			"description": challenge.get::<_, String>("description"),
+			"tags": challenge.get::<_, Vec<String>>("tags"),
+			"solves": challenge.get::<_, i32>("solves"),
+			"value": challenge.get::<_, i32>("value"),
+			"authenticated": challenge.get::<_, bool>("authenticated"),
+			"solved": challenge.get::<_, bool>("solved"),
+		})).collect::<Vec<_>>());
+	}, || html! {
 		style { "dialog{display:none;}dialog:target{display:block;}" }
-		h1 { "Challenges" }
+		h1 { (text.challenges) }
 		section class="challenges" {
 			ul {
 				@for challenge in &challenges {
@@ -131,11 +241,11 @@ fn get_challenges(mut client: Client, session: String, invalid: String) -> Resul
 					}
 					dialog open="open" id=(slug) {
 						h1 { (title) }
-						h4 class="value" { (value) " points" }
-						h4 class="solves" { (solves) " solves" }
+						h4 class="value" { (value) " " (text.points_unit) }
+						h4 class="solves" { (solves) " " (text.solves_unit) }
 						div class="description" { (PreEscaped(description)) }
-						h4 class="author" { "Author: " (author) }
-						h4 class="tags" { "Tags: "
+						h4 class="author" { (text.author) ": " (author) }
+						h4 class="tags" { (text.tags) ": "
 							@if tags.len() > 0 {
 								@for tag in &tags[0 .. tags.len() - 1] {
 									(tag) ", "
@@ -145,64 +255,99 @@ fn get_challenges(mut client: Client, session: String, invalid: String) -> Resul
 						}
 						@if authenticated {
 							@if invalid == slug {
-								p class="incorrect" { "Incorrect flag." }
+								p class="incorrect" { (text.incorrect_flag) }
 							}
 							@if solved {
-								p class="solved" { "Your team has solved this challenge." }
+								p class="solved" { (text.solved) }
 							} @else {
 								form class="submit" method="POST" {
 									input type="hidden" name="slug" value=(slug);
-									input type="text" name="flag" placeholder="Flag";
-									button type="submit" { "Submit" }
+									input type="text" name="flag" placeholder=(text.flag);
+									button type="submit" { (text.submit) }
 								}
 							}
 						}
-						a class="close" href="#!" { "Close" }
+						a class="close" href="#!" { (text.close) }
 					}
 				}
 			}
 		}
-	}, client, session)?, "set-cookie", "invalid=; HttpOnly; SameSite=Lax; Max-Age=-1"))
+	})?;
+	Ok(with_header(page(text, &settings, text.challenges, content, client, session)?,
+		"set-cookie", "invalid=; HttpOnly; SameSite=Lax; Max-Age=-1"))
 }
 
-fn get_scoreboard(mut client: Client, session: String) -> Result<impl Reply, Rejection> {
+fn get_scoreboard(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, graph: Arc<RwLock<Graph>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
 	let now = Utc::now();
 	let ctf = &result!(client.query("SELECT start, stop FROM scrap.ctf", &[]))[0];
 	if ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false) {
-		return Ok(page("Scoreboard", html! {
-			h1 { "Scoreboard" }
-			p { "Scoreboard is not available." }
-		}, client, session)?);
+		let content = content(text, &settings, "scoreboard", |context| {
+			context.insert("available", &false);
+			context.insert("graph", "");
+			context.insert("challenges", &Vec::<Value>::new());
+			context.insert("teams", &Vec::<Value>::new());
+		}, || html! {
+			h1 { (text.scoreboard) }
+			p { (text.scoreboard_unavailable) }
+		})?;
+		return Ok(page(text, &settings, text.scoreboard, content, client, session)?);
 	}
-	let teams = result!(client.query("SELECT name, score, solves, ROW_NUMBER()
+	let teams = result!(client.query("SELECT id, name, score, solves, ROW_NUMBER()
 		OVER (ORDER BY score DESC, submit ASC) AS place FROM scrap.team ORDER BY score DESC, submit ASC", &[]));
 	let challenges = result!(client.query("SELECT id, title FROM scrap.challenge
 		WHERE enabled=true
 		ORDER BY slug ASC", &[]));
-	Ok(page("Scoreboard", html! {
-		h1 { "Scoreboard" }
+	let svg = result!(Graph::render(&graph, &mut client));
+	let content = content(text, &settings, "scoreboard", |context| {
+		context.insert("available", &true);
+		context.insert("graph", &svg);
+		context.insert("challenges", &challenges.iter().map(|challenge| json!({
+			"id": challenge.get::<_, i32>("id"),
+			"title": challenge.get::<_, String>("title"),
+		})).collect::<Vec<_>>());
+		context.insert("teams", &teams.iter().map(|team| {
+			let solves: i64 = team.get("solves");
+			json!({
+				"id": team.get::<_, i32>("id"),
+				"name": team.get::<_, String>("name"),
+				"score": team.get::<_, i32>("score"),
+				"place": team.get::<_, i64>("place"),
+				"solved": challenges.iter()
+					.map(|challenge| challenge.get::<_, i32>("id"))
+					.filter(|id| 1 << (id - 1) & solves > 0)
+					.collect::<Vec<_>>(),
+			})
+		}).collect::<Vec<_>>());
+	}, || html! {
+		h1 { (text.scoreboard) }
+		@if svg.len() > 0 {
+			section class="graph" { (PreEscaped(&svg)) }
+		}
 		section class="scoreboard" {
 			table {
 				thead {
 					tr {
 						th class="place" { "#" }
-						th class="team" { "Team" }
+						th class="team" { (text.team) }
 						@for challenge in &challenges {
 							@let title: String = challenge.get("title");
 							th class="challenge" { (title) }
 						}
-						th class="score" { "Score" }
+						th class="score" { (text.score) }
 					}
 				}
 				tbody {
-					@for team in teams {
+					@for team in &teams {
+						@let team_id: i32 = team.get("id");
 						@let name: String = team.get("name");
 						@let solves: i64 = team.get("solves");
 						@let score: i32 = team.get("score");
 						@let place: i64 = team.get("place");
 						tr {
 							td class="place" { (place) }
-							td class="team" { (name) }
+							td class="team" { a href={ "/team/" (team_id) } { (name) } }
 							@for challenge in &challenges {
 								@let id: i32 = challenge.get("id");
 								@let mask: i64 = 1 << (id - 1);
@@ -217,113 +362,505 @@ fn get_scoreboard(mut client: Client, session: String) -> Result<impl Reply, Rej
 				}
 			}
 		}
-	}, client, session)?)
+	})?;
+	Ok(page(text, &settings, text.scoreboard, content, client, session)?)
 }
 
-fn make_profile(team: Option<Row>, error: Option<&str>) -> Markup {
-	html! {
-		h1 { "Profile" }
+fn get_notices(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	let announcements = result!(client.query("SELECT title, body, time FROM scrap.announcement
+		ORDER BY time DESC",
+		&[]));
+	result!(client.execute("UPDATE scrap.team
+		SET notice=(SELECT COALESCE(MAX(id), 0) FROM scrap.announcement)
+		WHERE id=lookup($1)",
+		&[&session]));
+	let content = content(text, &settings, "notices", |context| {
+		context.insert("announcements", &announcements.iter().map(|announcement| json!({
+			"title": announcement.get::<_, String>("title"),
+			"body": announcement.get::<_, String>("body"),
+			"time": announcement.get::<_, DateTime<Utc>>("time").to_rfc3339(),
+		})).collect::<Vec<_>>());
+	}, || html! {
+		h1 { (text.notices) }
+		section class="notices" {
+			@if announcements.len() > 0 {
+				ul {
+					@for announcement in &announcements {
+						@let title: String = announcement.get("title");
+						@let body: String = announcement.get("body");
+						@let time: DateTime<Utc> = announcement.get("time");
+						li {
+							h2 { (title) }
+							p class="time" { (time.format("%Y-%m-%d %H:%M UTC")) }
+							div class="body" { (PreEscaped(body)) }
+						}
+					}
+				}
+			} @else {
+				p class="empty" { (text.no_announcements) }
+			}
+		}
+	})?;
+	Ok(page(text, &settings, text.notices, content, client, session)?)
+}
+
+fn get_standings(mut client: Client) -> Result<impl Reply, Rejection> {
+	let now = Utc::now();
+	let ctf = &result!(client.query("SELECT start, stop FROM scrap.ctf", &[]))[0];
+	if ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false) {
+		return Ok(reply::json(&Standings::empty()));
+	}
+	Ok(reply::json(&result!(Standings::new(&mut client))))
+}
+
+fn get_team(id: i32, mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	let team = match result!(client.query("SELECT name, affiliation, score, place FROM (SELECT
+		id, name, affiliation, score, ROW_NUMBER()
+		OVER (ORDER BY score DESC, submit ASC) AS place FROM scrap.team) team
+		WHERE id=$1",
+		&[&id])).pop() {
+		Some(team) => team,
+		None => return Err(warp::reject::not_found()),
+	};
+	let name: String = team.get("name");
+	let affiliation: Option<String> = team.get("affiliation");
+	let now = Utc::now();
+	let ctf = &result!(client.query("SELECT start FROM scrap.ctf", &[]))[0];
+	if ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false) {
+		let content = content(text, &settings, "team", |context| {
+			context.insert("available", &false);
+			context.insert("name", &name);
+			context.insert("affiliation", &affiliation);
+			context.insert("solves", &Vec::<Value>::new());
+			context.insert("awards", &Vec::<Value>::new());
+		}, || html! {
+			h1 { (name) }
+			section class="team" {
+				@if let Some(affiliation) = &affiliation {
+					p class="affiliation" { (affiliation) }
+				}
+				p { (text.scoreboard_unavailable) }
+			}
+		})?;
+		return Ok(page(text, &settings, &name, content, client, session)?);
+	}
+	let solves = result!(client.query("SELECT
+		challenge.slug, challenge.title, value(challenge.solves) AS value, solve.time
+		FROM scrap.challenge challenge
+		JOIN scrap.team team ON team.id=$1
+		LEFT JOIN scrap.solve solve ON solve.team=team.id AND solve.challenge=challenge.id
+		WHERE enabled=true AND solved(team.solves, challenge.id)
+		ORDER BY solve.time ASC NULLS FIRST, challenge.slug ASC",
+		&[&id]));
+	let awards = result!(client.query("SELECT points, reason, time FROM scrap.award
+		WHERE team=$1
+		ORDER BY time ASC",
+		&[&id]));
+	let score: i32 = team.get("score");
+	let place: i64 = team.get("place");
+	let content = content(text, &settings, "team", |context| {
+		context.insert("available", &true);
+		context.insert("name", &name);
+		context.insert("affiliation", &affiliation);
+		context.insert("place", &place);
+		context.insert("score", &score);
+		context.insert("solves", &solves.iter().map(|solve| json!({
+			"slug": solve.get::<_, String>("slug"),
+			"title": solve.get::<_, String>("title"),
+			"value": solve.get::<_, i32>("value"),
+			"time": solve.get::<_, Option<DateTime<Utc>>>("time").map(|time| time.to_rfc3339()),
+		})).collect::<Vec<_>>());
+		context.insert("awards", &awards.iter().map(|award| json!({
+			"points": award.get::<_, i32>("points"),
+			"reason": award.get::<_, String>("reason"),
+			"time": award.get::<_, DateTime<Utc>>("time").to_rfc3339(),
+		})).collect::<Vec<_>>());
+	}, || html! {
+		h1 { (name) }
+		section class="team" {
+			@if let Some(affiliation) = &affiliation {
+				p class="affiliation" { (affiliation) }
+			}
+			p class="place" { "#" (place) }
+			p class="score" { (score) " " (text.points_unit) }
+			@if solves.len() > 0 {
+				h2 { (text.solves) }
+				table class="solves" {
+					thead {
+						tr {
+							th class="time" { (text.time) }
+							th class="challenge" { (text.challenge) }
+							th class="points" { (text.points) }
+						}
+					}
+					tbody {
+						@for solve in &solves {
+							@let slug: String = solve.get("slug");
+							@let title: String = solve.get("title");
+							@let value: i32 = solve.get("value");
+							@let time: Option<DateTime<Utc>> = solve.get("time");
+							tr {
+								td class="time" { @if let Some(time) = time { (time.format("%Y-%m-%d %H:%M UTC")) } }
+								td class="challenge" { a href={ "/challenges#" (slug) } { (title) } }
+								td class="points" { (value) }
+							}
+						}
+					}
+				}
+			}
+			@if awards.len() > 0 {
+				h2 { (text.awards) }
+				table class="awards" {
+					thead {
+						tr {
+							th class="time" { (text.time) }
+							th class="reason" { (text.reason) }
+							th class="points" { (text.points) }
+						}
+					}
+					tbody {
+						@for award in &awards {
+							@let points: i32 = award.get("points");
+							@let reason: String = award.get("reason");
+							@let time: DateTime<Utc> = award.get("time");
+							tr {
+								td class="time" { (time.format("%Y-%m-%d %H:%M UTC")) }
+								td class="reason" { (reason) }
+								td class="points" { (points) }
+							}
+						}
+					}
+				}
+			}
+		}
+	})?;
+	Ok(page(text, &settings, &name, content, client, session)?)
+}
+
+fn make_profile(text: &Text, settings: &Settings, team: Option<Row>, error: Option<&str>) -> Result<Markup, Rejection> {
+	content(text, settings, "profile", |context| {
+		context.insert("error", &error);
+		context.insert("team", &team.as_ref().map(|team| json!({
+			"id": team.get::<_, i32>("id"),
+			"name": team.get::<_, String>("name"),
+			"email": team.get::<_, String>("email"),
+			"affiliation": team.get::<_, Option<String>>("affiliation"),
+			"token": team.get::<_, Option<String>>("token"),
+		})));
+	}, || html! {
+		h1 { (text.profile) }
 		section class="profile" {
 			@if let Some(error) = error { p class="error" { (error) } }
-			@match team {
+			@match &team {
 				Some(team) => {
 					@let name: String = team.get("name");
+					@let id: i32 = team.get("id");
 					@let email: String = team.get("email");
+					@let affiliation: Option<String> = team.get("affiliation");
+					p class="public" { a href={ "/team/" (id) } { (text.public_page) } }
 					form method="POST" {
 						label {
-							"Team Name: "
+							(text.team_name) ": "
 							input type="text" disabled="disabled" value=(name);
 						}
 						label {
-							"Email: "
+							(text.email) ": "
 							input type="email" name="email" value=(email);
 						}
 						label {
-							"Password: "
-							input type="password" name="password" placeholder="Optional";
+							(text.affiliation) ": "
+							input type="text" name="affiliation" maxlength="64" placeholder=(text.optional)
+								value=(affiliation.unwrap_or_default());
 						}
 						label {
-							"Current Password: "
+							(text.password) ": "
+							input type="password" name="password" placeholder=(text.optional);
+						}
+						label {
+							(text.current_password) ": "
 							input type="password" name="current_password";
 						}
-						button type="submit" { "Save" }
+						button type="submit" { (text.save) }
+					}
+					@let token: Option<String> = team.get("token");
+					form class="token" method="POST" action="/profile/token" {
+						label {
+							(text.api_token) ": "
+							input type="text" readonly="readonly" value=(token.unwrap_or_default());
+						}
+						button type="submit" { (text.generate) }
 					}
 				},
 				None => {
-					p class="not-logged-in" { "Log in to view your profile." }
+					p class="not-logged-in" { (text.not_logged_in) }
 				}
 			}
 		}
-	}
+	})
 }
 
-fn get_profile(mut client: Client, session: String) -> Result<impl Reply, Rejection> {
-	let team = match client.query("SELECT name, email FROM scrap.team
+fn get_profile(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	let team = match client.query("SELECT id, name, email, affiliation, token FROM scrap.team
 		WHERE id=lookup($1)",
 		&[&session]) {
 		Ok(mut teams) => teams.pop(),
 		Err(e) => return Err(custom(e)),
 	};
-	Ok(page("Profile", make_profile(team, None), client, session)?)
+	Ok(page(text, &settings, text.profile, make_profile(text, &settings, team, None)?, client, session)?)
 }
 
-fn make_register(error: Option<&str>) -> Markup {
-	html! {
-		h1 { "Register" }
+fn make_register(text: &Text, settings: &Settings, error: Option<&str>) -> Result<Markup, Rejection> {
+	content(text, settings, "register", |context| {
+		context.insert("error", &error);
+	}, || html! {
+		h1 { (text.register) }
 		section class="register" {
 			@if let Some(error) = error { p class="error" { (error) } }
 			form method="POST" {
-				input type="text" name="name" placeholder="Team Name" maxlength="64" pattern="[ -~]+";
-				input type="email" name="email" placeholder="Email";
-				input type="password" name="password" placeholder="Password";
-				button type="submit" { "Register" }
+				input type="text" name="name" placeholder=(text.team_name) maxlength="64" pattern="[ -~]+";
+				input type="email" name="email" placeholder=(text.email);
+				input type="password" name="password" placeholder=(text.password);
+				input type="text" name="affiliation" placeholder=(text.affiliation_optional) maxlength="64";
+				button type="submit" { (text.register) }
 			}
 		}
-	}
+	})
 }
 
-fn get_register(client: Client, session: String) -> Result<impl Reply, Rejection> {
-	Ok(page("Register", make_register(None), client, session)?)
+fn get_register(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	Ok(page(text, &settings, text.register, make_register(text, &settings, None)?, client, session)?)
 }
 
-fn make_login(error: Option<&str>) -> Markup {
-	html! {
-		h1 { "Login" }
+fn make_login(text: &Text, settings: &Settings, error: Option<&str>) -> Result<Markup, Rejection> {
+	content(text, settings, "login", |context| {
+		context.insert("error", &error);
+	}, || html! {
+		h1 { (text.login) }
 		section class="login" {
 			@if let Some(error) = error { p class="error" { (error) } }
 			form method="POST" {
-				input type="text" name="name" placeholder="Team Name";
-				input type="password" name="password" placeholder="Password";
-				button type="submit" { "Log In" }
+				input type="text" name="name" placeholder=(text.team_name);
+				input type="password" name="password" placeholder=(text.password);
+				button type="submit" { (text.log_in) }
+			}
+		}
+	})
+}
+
+fn get_login(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	Ok(page(text, &settings, text.login, make_login(text, &settings, None)?, client, session)?)
+}
+
+fn authorize(client: &mut Client, session: &str) -> Result<(), Rejection> {
+	let admins = result!(client.query("SELECT id FROM scrap.team
+		WHERE id=lookup($1) AND admin",
+		&[&session]));
+	if admins.len() > 0 {
+		Ok(())
+	} else {
+		Err(warp::reject::not_found())
+	}
+}
+
+fn make_admin(text: &Text, awards: Vec<Row>, announcements: Vec<Row>, error: Option<&str>) -> Markup {
+	html! {
+		h1 { (text.admin) }
+		section class="admin" {
+			@if let Some(error) = error { p class="error" { (error) } }
+			h2 { (text.awards) }
+			table class="awards" {
+				thead {
+					tr {
+						th class="time" { (text.time) }
+						th class="team" { (text.team) }
+						th class="reason" { (text.reason) }
+						th class="points" { (text.points) }
+						th class="revoke" {}
+					}
+				}
+				tbody {
+					@for award in &awards {
+						@let id: i32 = award.get("id");
+						@let team: i32 = award.get("team");
+						@let name: String = award.get("name");
+						@let points: i32 = award.get("points");
+						@let reason: String = award.get("reason");
+						@let time: DateTime<Utc> = award.get("time");
+						tr {
+							td class="time" { (time.format("%Y-%m-%d %H:%M UTC")) }
+							td class="team" { a href={ "/team/" (team) } { (name) } }
+							td class="reason" { (reason) }
+							td class="points" { (points) }
+							td class="revoke" {
+								form method="POST" action="/admin/revoke" {
+									input type="hidden" name="id" value=(id);
+									button type="submit" { (text.revoke) }
+								}
+							}
+						}
+					}
+				}
+			}
+			form class="award" method="POST" action="/admin/award" {
+				input type="text" name="team" placeholder=(text.team_name);
+				input type="number" name="points" placeholder=(text.points);
+				input type="text" name="reason" placeholder=(text.reason);
+				button type="submit" { (text.award) }
+			}
+			h2 { (text.announcements) }
+			table class="announcements" {
+				thead {
+					tr {
+						th class="time" { (text.time) }
+						th class="title" { (text.title) }
+						th class="retract" {}
+					}
+				}
+				tbody {
+					@for announcement in &announcements {
+						@let id: i32 = announcement.get("id");
+						@let title: String = announcement.get("title");
+						@let time: DateTime<Utc> = announcement.get("time");
+						tr {
+							td class="time" { (time.format("%Y-%m-%d %H:%M UTC")) }
+							td class="title" { (title) }
+							td class="retract" {
+								form method="POST" action="/admin/retract" {
+									input type="hidden" name="id" value=(id);
+									button type="submit" { (text.retract) }
+								}
+							}
+						}
+					}
+				}
+			}
+			form class="announce" method="POST" action="/admin/announce" {
+				input type="text" name="title" placeholder=(text.title);
+				textarea name="body" placeholder=(text.markdown) {}
+				button type="submit" { (text.announce) }
 			}
 		}
 	}
 }
 
-fn get_login(client: Client, session: String) -> Result<impl Reply, Rejection> {
-	Ok(page("Login", make_login(None), client, session)?)
+fn make_admin_body(text: &Text, settings: &Settings, mut client: Client, session: String, error: Option<&str>) -> Result<String, Rejection> {
+	let awards = result!(client.query("SELECT award.id, team, name, points, reason, time
+		FROM scrap.award award
+		JOIN scrap.team team ON team.id=award.team
+		ORDER BY time DESC",
+		&[]));
+	let announcements = result!(client.query("SELECT id, title, time FROM scrap.announcement
+		ORDER BY time DESC",
+		&[]));
+	make_body(text, settings, text.admin, make_admin(text, awards, announcements, error), client, session)
+}
+
+fn get_admin(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>) -> Result<impl Reply, Rejection> {
+	authorize(&mut client, &session)?;
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	Ok(make_reply(make_admin_body(text, &settings, client, session, None)?))
+}
+
+/// Marks a response as failed with its status, so that `finish` can draw the error page.
+struct Failure(StatusCode);
+
+/// The status for a rejection, where the database being unreachable is temporary and its errors are not.
+fn status(err: &Rejection) -> StatusCode {
+	if err.find_cause::<r2d2::Error>().is_some() {
+		StatusCode::SERVICE_UNAVAILABLE
+	} else if err.find_cause::<postgres::Error>().is_some() {
+		StatusCode::INTERNAL_SERVER_ERROR
+	} else {
+		err.status()
+	}
 }
 
 fn error(err: Rejection) -> Result<impl Reply, Rejection> {
-	match err.status() {
-		StatusCode::METHOD_NOT_ALLOWED => {
-			Ok(Response::builder()
-				.status(StatusCode::NOT_FOUND)
-				.body("404 Page Not Found"))
-		},
-		_ => {
-			Ok(Response::builder()
-				.status(StatusCode::INTERNAL_SERVER_ERROR)
-				.body("500 Internal Server Error"))
-		}
+	let status = status(&err);
+	let cause = err.find_cause::<r2d2::Error>().map(|e| e.to_string())
+		.or_else(|| err.find_cause::<postgres::Error>().map(|e| e.to_string()))
+		.unwrap_or_else(|| format!("{:?}", err));
+	if status.is_server_error() {
+		error!("event=rejection status={} error={}", status.as_u16(), quote(&cause));
+	} else {
+		warn!("event=rejection status={} error={}", status.as_u16(), quote(&cause));
 	}
+	Ok(Response::builder()
+		.status(status)
+		.extension(Failure(status))
+		.body(String::new()))
 }
 
-fn submit(mut client: Client, session: String, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
-	let now = Utc::now();
-	let ctf = &result!(client.query("SELECT start, stop FROM scrap.ctf", &[]))[0];
-	if ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false) || 
-		ctf.try_get::<_, DateTime<Utc>>("stop").map(|stop| now > stop).unwrap_or(false) {
+/// Draws the error page for a response marked by `error`, in the request's language and with its session,
+/// or a JSON error under `/api/`. Other responses are passed through.
+fn finish(path: FullPath, session: String, language: Option<String>, site: Arc<RwLock<Site>>, pool: ClientPool, reply: impl Reply) -> reply::Response {
+	let response = reply.into_response();
+	let status = match response.extensions().get::<Failure>() {
+		Some(&Failure(status)) => status,
+		None => return response,
+	};
+	// The database is not waited on when it is the cause of the error. Without it, the page is drawn
+	// with the settings loaded last, and a session cookie is taken to mean that a team is logged in.
+	let client = match status {
+		StatusCode::SERVICE_UNAVAILABLE => None,
+		_ => pool.get_timeout(Duration::from_secs(1)).ok(),
+	};
+	let (settings, client) = match client.map(|mut client| (Site::load(&site, &mut client), client)) {
+		Some((Ok(settings), client)) => (settings, Some(client)),
+		_ => (Site::settings(&site), None),
+	};
+	let text = settings.text(&language);
+	let (title, message) = text.error(status.as_u16());
+	let mut response = if path.as_str().starts_with("/api/") {
+		api::failure(status, message).into_response()
+	} else {
+		let logged_in = !session.is_empty();
+		let body = match client {
+			Some(client) => error_page(text, &settings, status)
+				.and_then(|content| make_body(text, &settings, title, content, client, session)),
+			None => error_page(text, &settings, status)
+				.and_then(|content| layout(text, &settings, title, content, logged_in, 0)),
+		}.or_else(|_| layout(text, &Settings::default(), title, error_page(text, &Settings::default(), status)?, logged_in, 0));
+		Response::builder()
+			.status(status)
+			.header("content-type", "text/html; charset=utf-8")
+			.header("content-security-policy", "script-src 'none'")
+			.body(body.unwrap_or_default())
+			.into_response()
+	};
+	if status == StatusCode::SERVICE_UNAVAILABLE || status == StatusCode::TOO_MANY_REQUESTS {
+		response.headers_mut().insert("retry-after", HeaderValue::from_static("5"));
+	}
+	response
+}
+
+fn error_page(text: &Text, settings: &Settings, status: StatusCode) -> Result<Markup, Rejection> {
+	let (title, message) = text.error(status.as_u16());
+	content(text, settings, "error", |context| {
+		context.insert("status", &status.as_u16());
+		context.insert("title", title);
+		context.insert("message", message);
+	}, || html! {
+		h1 { (title) }
+		section class="error" {
+			p { (message) }
+		}
+	})
+}
+
+fn submit(mut client: Client, session: String, form: HashMap<String, String>, webhooks: Webhooks, metrics: Arc<Metrics>) -> Result<impl Reply, Rejection> {
+	if !result!(submission::open(&mut client)) {
 		return Ok(Response::builder()
 			.header("location", "/challenges")
 			.status(StatusCode::SEE_OTHER)
@@ -332,25 +869,17 @@ fn submit(mut client: Client, session: String, form: HashMap<String, String>) ->
 	let empty = String::new();
 	let slug = form.get("slug").unwrap_or(&empty);
 	let flag = form.get("flag").unwrap_or(&empty);
-	let mut transaction = result!(client.transaction());
-	let rows = result!(transaction.execute("UPDATE scrap.team team
-		SET solves=update(team.solves, challenge.id), submit=NOW()
-		FROM scrap.challenge challenge
-		WHERE team.id=lookup($1)
-		AND slug=$2 AND flag=$3
-		AND NOT solved(team.solves, challenge.id)",
-		&[&session, &slug, &flag])) as i32;
-	if rows > 0 {
-		result!(transaction.execute("UPDATE scrap.challenge
-			SET solves=solves+$2
-			WHERE slug=$1",
-			&[&slug, &rows]));
-		result!(transaction.execute("UPDATE scrap.team team
-			SET score=COALESCE((SELECT SUM(value(challenge.solves))
-			FROM scrap.challenge challenge
-			WHERE solved(team.solves, challenge.id)), 0)",
-			&[]));
-		result!(transaction.commit());
+	let team: Option<i32> = result!(client.query("SELECT lookup($1) AS team", &[&session]))[0].get("team");
+	let outcome = match team {
+		Some(team) => {
+			let outcome = result!(submission::attempt(&mut client, team, slug, flag));
+			submission::announce(&webhooks, &outcome, team, slug);
+			outcome
+		},
+		None => Outcome::Incorrect,
+	};
+	metrics.submission(&outcome);
+	if outcome != Outcome::Incorrect {
 		return Ok(Response::builder()
 			.header("location", "/challenges")
 			.status(StatusCode::SEE_OTHER)
@@ -364,8 +893,10 @@ fn submit(mut client: Client, session: String, form: HashMap<String, String>) ->
 	}
 }
 
-fn edit(mut client: Client, session: String, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
-	let team = match client.query("SELECT name, email FROM scrap.team
+fn edit(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	let team = match client.query("SELECT id, name, email, affiliation, token FROM scrap.team
 		WHERE id=lookup($1)",
 		&[&session]) {
 		Ok(mut teams) => teams.pop(),
@@ -378,27 +909,37 @@ fn edit(mut client: Client, session: String, form: HashMap<String, String>) -> R
 				_ => return Ok(Response::builder()
 					.status(StatusCode::BAD_REQUEST)
 					.header("content-security-policy", "script-src 'none'")
-					.body(make_body("Profile", make_profile(team, Some($error)), client, session)?)),
+					.body(make_body(text, &settings, text.profile, make_profile(text, &settings, team, Some($error))?, client, session)?)),
 			}
 		}
 	}
-	let email = profile_form!(form.get("email"), "Email is required.", false);
+	let email = profile_form!(form.get("email"), text.email_required, false);
+	let affiliation = profile_form!(form.get("affiliation"), "", true);
 	let password = profile_form!(form.get("password"), "", true);
-	let current_password = profile_form!(form.get("current_password"), "Current password is required.", false);
+	let current_password = profile_form!(form.get("current_password"), text.current_password_required, false);
+	if affiliation.len() > 64 {
+		return Ok(Response::builder()
+			.status(StatusCode::BAD_REQUEST)
+			.header("content-security-policy", "script-src 'none'")
+			.body(make_body(text, &settings, text.profile, make_profile(text, &settings, team, Some(text.affiliation_too_long))?, client, session)?))
+	}
 	match client.execute("UPDATE scrap.team
-		SET email=$2, hash=CASE WHEN ($3 != '') THEN crypt($3, gen_salt('bf')) ELSE hash END
+		SET email=$2, hash=CASE WHEN ($3 != '') THEN crypt($3, gen_salt('bf')) ELSE hash END,
+		affiliation=NULLIF($5, '')
 		WHERE id=lookup($1)
 		AND hash=crypt($4, hash)",
-		&[&session, &email, &password, &current_password]) {
-		Ok(n) if n > 0 => (),
+		&[&session, &email, &password, &current_password, &affiliation]) {
+		Ok(n) if n > 0 => if let Some(team) = &team {
+			info!("event=profile_edit id={}", team.get::<_, i32>("id"));
+		},
 		Ok(_) => return Ok(Response::builder()
 			.status(StatusCode::UNAUTHORIZED)
 			.header("content-security-policy", "script-src 'none'")
-			.body(make_body("Profile", make_profile(team, Some("Incorrect password.")), client, session)?)),
+			.body(make_body(text, &settings, text.profile, make_profile(text, &settings, team, Some(text.incorrect_password))?, client, session)?)),
 		Err(ref e) if e.code() == Some(&SqlState::UNIQUE_VIOLATION) => return Ok(Response::builder()
 			.status(StatusCode::BAD_REQUEST)
 			.header("content-security-policy", "script-src 'none'")
-			.body(make_body("Profile", make_profile(team, Some("Email conflict.")), client, session)?)),
+			.body(make_body(text, &settings, text.profile, make_profile(text, &settings, team, Some(text.email_conflict))?, client, session)?)),
 		Err(e) => return Err(custom(e)),
 	}
 	Ok(Response::builder()
@@ -407,29 +948,55 @@ fn edit(mut client: Client, session: String, form: HashMap<String, String>) -> R
 		.body("".to_string()))
 }
 
-fn register(mut client: Client, session: String, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+fn generate(mut client: Client, session: String) -> Result<impl Reply, Rejection> {
+	result!(client.execute("UPDATE scrap.team
+		SET token=encode(gen_random_bytes(24), 'hex')
+		WHERE id=lookup($1)",
+		&[&session]));
+	Ok(Response::builder()
+		.header("location", "/profile")
+		.status(StatusCode::SEE_OTHER)
+		.body("".to_string()))
+}
+
+fn register(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, form: HashMap<String, String>, webhooks: Webhooks) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
 	macro_rules! register_form {
 		($field:expr, $error:expr) => {
-			form!($field, "Registration", $error, make_register, client, session)
+			form!($field, text.registration, $error, make_register, text, settings, client, session)
 		}
 	}
-	let name = register_form!(form.get("name"), "Team name is required.");
-	let email = register_form!(form.get("email"), "Email is required.");
-	let password = register_form!(form.get("password"), "Password is required.");
+	let name = register_form!(form.get("name"), text.team_name_required);
+	let email = register_form!(form.get("email"), text.email_required);
+	let password = register_form!(form.get("password"), text.password_required);
+	let empty = String::new();
+	let affiliation = form.get("affiliation").unwrap_or(&empty);
 	if name.len() > 64 || !name.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
 		return Ok(Response::builder()
 			.status(StatusCode::BAD_REQUEST)
 			.header("content-security-policy", "script-src 'none'")
-			.body(make_body("Registration", make_register(Some("Invalid team name length or characters.")), client, session)?))
+			.body(make_body(text, &settings, text.registration, make_register(text, &settings, Some(text.invalid_team_name))?, client, session)?))
+	}
+	if affiliation.len() > 64 {
+		return Ok(Response::builder()
+			.status(StatusCode::BAD_REQUEST)
+			.header("content-security-policy", "script-src 'none'")
+			.body(make_body(text, &settings, text.registration, make_register(text, &settings, Some(text.affiliation_too_long))?, client, session)?))
 	}
-	match client.execute("INSERT INTO scrap.team
-		(name, email, hash) VALUES ($1, $2, crypt($3, gen_salt('bf')))",
-		&[name, email, password]) {
-		Ok(_) => (),
+	match client.query("INSERT INTO scrap.team
+		(name, email, hash, affiliation) VALUES ($1, $2, crypt($3, gen_salt('bf')), NULLIF($4, ''))
+		RETURNING id",
+		&[name, email, password, affiliation]) {
+		Ok(teams) => {
+			let id: i32 = teams[0].get("id");
+			info!("event=register team={} id={}", quote(name), id);
+			webhooks.send(Event { kind: "register", team: id, challenge: None });
+		},
 		Err(ref e) if e.code() == Some(&SqlState::UNIQUE_VIOLATION) => return Ok(Response::builder()
 			.status(StatusCode::BAD_REQUEST)
 			.header("content-security-policy", "script-src 'none'")
-			.body(make_body("Registration", make_register(Some("Team name or email conflict.")), client, session)?)),
+			.body(make_body(text, &settings, text.registration, make_register(text, &settings, Some(text.team_conflict))?, client, session)?)),
 		Err(e) => return Err(custom(e)),
 	}
 	Ok(Response::builder()
@@ -438,26 +1005,32 @@ fn register(mut client: Client, session: String, form: HashMap<String, String>)
 		.body("".to_string()))
 }
 
-fn login(mut client: Client, session: String, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+fn login(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, form: HashMap<String, String>, secure: bool) -> Result<impl Reply, Rejection> {
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
 	macro_rules! login_form {
 		($field:expr, $error:expr) => {
-			form!($field, "Login", $error, make_login, client, session)
+			form!($field, text.login, $error, make_login, text, settings, client, session)
 		}
 	}
-	let name = login_form!(form.get("name"), "Team name is required.");
-	let password = login_form!(form.get("password"), "Password is required.");
+	let name = login_form!(form.get("name"), text.team_name_required);
+	let password = login_form!(form.get("password"), text.password_required);
 	let id: i32 = match client.query("SELECT id FROM scrap.team
 		WHERE name=$1 AND hash=crypt($2, hash)",
 		&[name, password]) {
 		Ok(teams) => match teams.get(0) {
 			Some(team) => team.get("id"),
-			None => return Ok(Response::builder()
-				.status(StatusCode::BAD_REQUEST)
-				.header("content-security-policy", "script-src 'none'")
-				.body(make_body("Login", make_login(Some("Invalid team name or password.")), client, session)?)),
+			None => {
+				warn!("event=login outcome=failure team={}", quote(name));
+				return Ok(Response::builder()
+					.status(StatusCode::BAD_REQUEST)
+					.header("content-security-policy", "script-src 'none'")
+					.body(make_body(text, &settings, text.login, make_login(text, &settings, Some(text.invalid_login))?, client, session)?))
+			},
 		},
 		Err(e) => return Err(custom(e)),
 	};
+	info!("event=login outcome=success team={} id={}", quote(name), id);
 	let cookie: String = match client.query("INSERT INTO scrap.session
 		(team, cookie) VALUES ($1, gen_random_uuid())
 		RETURNING cookie",
@@ -467,50 +1040,447 @@ fn login(mut client: Client, session: String, form: HashMap<String, String>) ->
 	};
 	Ok(Response::builder()
 		.header("location", "/challenges")
-		.header("set-cookie", format!("session={}; HttpOnly; SameSite=Lax; Max-Age=31536000", cookie))
+		.header("set-cookie", format!("session={}; HttpOnly; SameSite=Lax; Max-Age=31536000{}",
+			cookie, if secure { "; Secure" } else { "" }))
+		.status(StatusCode::SEE_OTHER)
+		.body("".to_string()))
+}
+
+macro_rules! admin_form {
+	($field:expr, $error:expr, $text:ident, $settings:ident, $client:ident, $session:ident) => {
+		match $field {
+			Some(value) if value.len() > 0 => value,
+			_ => return Ok(Response::builder()
+				.status(StatusCode::BAD_REQUEST)
+				.header("content-security-policy", "script-src 'none'")
+				.body(make_admin_body($text, &$settings, $client, $session, Some($error))?)),
+		}
+	}
+}
+
+fn award(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+	authorize(&mut client, &session)?;
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	let team = admin_form!(form.get("team"), text.team_name_required, text, settings, client, session);
+	let points = admin_form!(form.get("points").filter(|points| points.parse::<i32>().is_ok()),
+		text.points_integer, text, settings, client, session);
+	let reason = admin_form!(form.get("reason"), text.reason_required, text, settings, client, session);
+	let award = Award {
+		team: team.to_string(),
+		points: points.parse().unwrap(),
+		reason: reason.to_string(),
+	};
+	if result!(award.push(&mut client)) {
+		info!("event=award team={} points={} reason={}", quote(team), award.points, quote(reason));
+	} else {
+		return Ok(Response::builder()
+			.status(StatusCode::BAD_REQUEST)
+			.header("content-security-policy", "script-src 'none'")
+			.body(make_admin_body(text, &settings, client, session, Some(text.team_not_found))?))
+	}
+	Ok(Response::builder()
+		.header("location", "/admin")
+		.status(StatusCode::SEE_OTHER)
+		.body("".to_string()))
+}
+
+fn revoke(mut client: Client, session: String, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+	authorize(&mut client, &session)?;
+	if let Some(id) = form.get("id").and_then(|id| id.parse().ok()) {
+		if result!(Award::revoke(id, &mut client)) {
+			info!("event=revoke award={}", id);
+		}
+	}
+	Ok(Response::builder()
+		.header("location", "/admin")
+		.status(StatusCode::SEE_OTHER)
+		.body("".to_string()))
+}
+
+fn announce(mut client: Client, session: String, language: Option<String>, site: Arc<RwLock<Site>>, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+	authorize(&mut client, &session)?;
+	let settings = result!(Site::load(&site, &mut client));
+	let text = settings.text(&language);
+	let title = admin_form!(form.get("title"), text.title_required, text, settings, client, session);
+	let body = admin_form!(form.get("body"), text.body_required, text, settings, client, session);
+	let mut transaction = result!(client.transaction());
+	result!(Announcement::new(title, body).push(&mut transaction));
+	result!(transaction.commit());
+	info!("event=announce title={}", quote(title));
+	Ok(Response::builder()
+		.header("location", "/admin")
+		.status(StatusCode::SEE_OTHER)
+		.body("".to_string()))
+}
+
+fn retract(mut client: Client, session: String, form: HashMap<String, String>) -> Result<impl Reply, Rejection> {
+	authorize(&mut client, &session)?;
+	if let Some(id) = form.get("id").and_then(|id| id.parse::<i32>().ok()) {
+		if result!(client.execute("DELETE FROM scrap.announcement WHERE id=$1", &[&id])) > 0 {
+			info!("event=retract announcement={}", id);
+		}
+	}
+	Ok(Response::builder()
+		.header("location", "/admin")
 		.status(StatusCode::SEE_OTHER)
 		.body("".to_string()))
 }
 
+fn get_healthz() -> impl Reply {
+	reply::with_status("ok", StatusCode::OK)
+}
+
+fn get_readyz(pool: ClientPool) -> impl Reply {
+	let ready = pool.get_timeout(Duration::from_secs(1)).ok()
+		.and_then(|mut client| client.query("SELECT COUNT(*) AS count FROM scrap.ctf", &[]).ok())
+		.map(|ctfs| ctfs[0].get::<_, i64>("count") > 0)
+		.unwrap_or(false);
+	if ready {
+		reply::with_status("ok", StatusCode::OK)
+	} else {
+		reply::with_status("unavailable", StatusCode::SERVICE_UNAVAILABLE)
+	}
+}
+
+fn get_metrics(pool: ClientPool, metrics: Arc<Metrics>) -> impl Reply {
+	with_header(metrics.render(&pool), "content-type", "text/plain; version=0.0.4")
+}
+
 fn logout(mut client: Client, session: String) -> Result<impl Reply, Rejection> {
-	match client.execute("DELETE FROM scrap.session
-		WHERE cookie=$1",
+	match client.query("DELETE FROM scrap.session
+		WHERE cookie=$1
+		RETURNING team",
 		&[&session]) {
-		Ok(_n) => Ok(Response::builder()
-			.header("location", "/")
-			.status(StatusCode::SEE_OTHER)
-			.body("".to_string())),
+		Ok(sessions) => {
+			for session in &sessions {
+				info!("event=logout id={}", session.get::<_, i32>("team"));
+			}
+			Ok(Response::builder()
+				.header("location", "/")
+				.status(StatusCode::SEE_OTHER)
+				.body("".to_string()))
+		},
 		Err(e) => return Err(custom(e)),
 	}
 }
 
-pub fn run(port: u16, pool: ClientPool) {
-	let client = any().map(move || pool.get().unwrap());
+pub fn run(listen: Listen, tls: Option<Tls>, static_path: Option<PathBuf>, pool: ClientPool) -> Result<(), Error> {
+	let metrics = Arc::new(Metrics::default());
+	let log = {
+		let metrics = metrics.clone();
+		warp::log::custom(move |info| {
+			metrics.request(info.path(), info.method().as_str(), info.status().as_u16(), info.elapsed());
+			info!("event=request method={} path={} status={} elapsed={:?} remote={}",
+				info.method(), quote(info.path()), info.status().as_u16(), info.elapsed(),
+				info.remote_addr().map(|addr| addr.ip().to_string()).unwrap_or("-".to_string()));
+		})
+	};
+	let metrics = any().map(move || metrics.clone());
+	let secure = tls.is_some();
+	let secure = any().map(move || secure);
+	let webhooks = Webhooks::new(pool.clone());
+	let webhooks = any().map(move || webhooks.clone());
+	let client = {
+		let pool = pool.clone();
+		let metrics = metrics.clone();
+		any().and(metrics).and_then(move |metrics: Arc<Metrics>| {
+			let start = Instant::now();
+			let client = pool.get_timeout(CHECKOUT_TIMEOUT);
+			metrics.wait(start.elapsed());
+			client.map_err(custom)
+		})
+	};
+	let site = Arc::new(RwLock::new(Site::default()));
+	let site = any().map(move || site.clone());
+	let pool = any().map(move || pool.clone());
 	let session = warp::cookie::optional("session")
 		.map(|cookie: Option<String>| cookie.unwrap_or(String::new()));
 	let invalid = warp::cookie::optional("invalid")
 		.map(|cookie: Option<String>| cookie.unwrap_or(String::new()));
+	let graph = Arc::new(RwLock::new(Graph::default()));
+	let graph = any().map(move || graph.clone());
+	let token = api::token();
+	let language = warp::header::optional::<String>("accept-language");
 	let get = warp::get2().and(client.clone()).and(session.clone());
 	let post = warp::post2().and(client.clone()).and(session.clone());
-	let routes = get.clone().and(end()).and_then(get_home)
-		.or(get.clone().and(path("challenges")).and(end()).and(invalid.clone()).and_then(get_challenges))
-		.or(get.clone().and(path("scoreboard")).and(end()).and_then(get_scoreboard))
-		.or(get.clone().and(path("profile")).and(end()).and_then(get_profile))
-		.or(get.clone().and(path("register")).and(end()).and_then(get_register))
-		.or(get.clone().and(path("login")).and(end()).and_then(get_login))
-		.or(post.clone().and(path("challenges")).and(end())
+	// Paths are matched before methods, so that unknown paths are 404 and known paths with the wrong method are 405,
+	// and before database connections, so that one is only acquired for the matching route.
+	let routes = path("healthz").and(end()).and(warp::get2()).map(get_healthz)
+		.or(path("readyz").and(end()).and(warp::get2()).and(pool.clone()).map(get_readyz))
+		.or(static_filter(static_path))
+		.or(end().and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_home))
+		.or(path("challenges").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and(invalid.clone()).and_then(get_challenges))
+		.or(path("challenges").and(end()).and(post.clone())
 			.and(body::content_length_limit(4096))
-			.and(body::form()).and_then(submit))
-		.or(post.clone().and(path("profile")).and(end())
+			.and(body::form()).and(webhooks.clone()).and(metrics.clone()).and_then(submit))
+		.or(path("scoreboard").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and(graph).and_then(get_scoreboard))
+		.or(path("scoreboard.json").and(end()).and(warp::get2()).and(client.clone()).and_then(get_standings))
+		.or(path("profile").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_profile))
+		.or(path("profile").and(end()).and(post.clone()).and(language.clone()).and(site.clone())
 			.and(body::content_length_limit(4096))
 			.and(body::form()).and_then(edit))
-		.or(post.clone().and(path("register")).and(end())
+		.or(path("profile").and(path("token")).and(end()).and(post.clone()).and_then(generate))
+		.or(path("register").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_register))
+		.or(path("register").and(end()).and(post.clone()).and(language.clone()).and(site.clone())
+			.and(body::content_length_limit(4096))
+			.and(body::form()).and(webhooks.clone()).and_then(register))
+		.or(path("login").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_login))
+		.or(path("login").and(end()).and(post.clone()).and(language.clone()).and(site.clone())
+			.and(body::content_length_limit(4096))
+			.and(body::form()).and(secure).and_then(login))
+		.or(path("notices").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_notices))
+		.or(path("team").and(param()).and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_team))
+		.or(path("admin").and(end()).and(get.clone()).and(language.clone()).and(site.clone()).and_then(get_admin))
+		.or(path("admin").and(path("award")).and(end()).and(post.clone()).and(language.clone()).and(site.clone())
 			.and(body::content_length_limit(4096))
-			.and(body::form()).and_then(register))
-		.or(post.clone().and(path("login")).and(end())
+			.and(body::form()).and_then(award))
+		.or(path("admin").and(path("revoke")).and(end()).and(post.clone())
 			.and(body::content_length_limit(4096))
-			.and(body::form()).and_then(login))
-		.or(get.clone().and(path("logout")).and(end()).and_then(logout))
+			.and(body::form()).and_then(revoke))
+		.or(path("admin").and(path("announce")).and(end()).and(post.clone()).and(language.clone()).and(site.clone())
+			.and(body::content_length_limit(65536))
+			.and(body::form()).and_then(announce))
+		.or(path("admin").and(path("retract")).and(end()).and(post.clone())
+			.and(body::content_length_limit(4096))
+			.and(body::form()).and_then(retract))
+		.or(path("logout").and(end()).and(get.clone()).and_then(logout))
+		.or(path("api").and(path("v1")).and(path("challenges")).and(end())
+			.and(warp::get2()).and(client.clone()).and(token.clone()).and(language.clone()).and(site.clone())
+			.and_then(api::challenges))
+		.or(path("api").and(path("v1")).and(path("submit")).and(end())
+			.and(warp::post2()).and(client.clone()).and(token.clone()).and(language.clone()).and(site.clone())
+			.and(body::content_length_limit(4096))
+			.and(body::json()).and(webhooks.clone()).and(metrics.clone()).and_then(api::submit))
+		.or(path("api").and(path("v1")).and(path("scoreboard")).and(end())
+			.and(warp::get2()).and(client.clone()).and(token.clone()).and(language.clone()).and(site.clone())
+			.and_then(api::scoreboard))
+		.or(path("api").and(path("v1")).and(path("me")).and(end())
+			.and(warp::get2()).and(client.clone()).and(token.clone()).and(language.clone()).and(site.clone())
+			.and_then(api::me))
+		.or(path("metrics").and(end()).and(warp::get2())
+			.and(pool.clone()).and(metrics.clone()).map(get_metrics))
 		.recover(error);
-	warp::serve(routes).run(([127, 0, 0, 1], port));
+	// Malformed headers leave an error page without a session or language, rather than without the page.
+	let routes = full()
+		.and(session.or(any().map(String::new)).unify())
+		.and(language.or(any().map(|| None)).unify())
+		.and(site).and(pool).and(routes)
+		.map(finish)
+		.with(log);
+	match (listen, tls) {
+		(Listen::Tcp(address), Some(tls)) => {
+			if let Some(port) = tls.redirect {
+				let redirect = SocketAddr::new(address.ip(), port);
+				let (redirect, server) = warp::serve(redirect_filter(address.port())).try_bind_ephemeral(redirect)
+					.map_err(|err| Error::Bind(redirect, io::Error::other(err)))?;
+				info!("event=listen address={} redirect=true", redirect);
+				thread::spawn(move || tokio::run(server));
+			}
+			let acceptor = TlsAcceptor::from(tls.acceptor);
+			let listener = TcpListener::bind(&address).map_err(|err| Error::Bind(address, err))?;
+			info!("event=listen address={} tls=true", address);
+			// A failed or stalled handshake drops that connection instead of stopping the server.
+			let incoming = listener.incoming().then(accepted).filter_map(|stream| stream)
+				.map(move |stream| acceptor.accept(stream)
+					.timeout(HANDSHAKE_TIMEOUT)
+					.then(|result| Ok::<_, io::Error>(result.map_err(|err| {
+						info!("event=handshake error={}", quote(&err.to_string()));
+					}).ok())))
+				.buffer_unordered(HANDSHAKES)
+				.filter_map(|stream| stream);
+			tokio::run(warp::serve(routes).serve_incoming(incoming));
+		},
+		(Listen::Unix(_), Some(_)) => return Err(Error::Message("TLS requires an IP address to bind.")),
+		(Listen::Tcp(address), None) => {
+			let (address, server) = warp::serve(routes).try_bind_ephemeral(address)
+				.map_err(|err| Error::Bind(address, io::Error::other(err)))?;
+			info!("event=listen address={} tls=false", address);
+			tokio::run(server);
+		},
+		(Listen::Unix(path), None) => {
+			// A socket left behind by a previous run would make binding fail.
+			if fs::symlink_metadata(&path).map(|metadata| metadata.file_type().is_socket()).unwrap_or(false) {
+				fs::remove_file(&path).map_err(|err| Error::Listen(path.clone(), err))?;
+			}
+			let listener = UnixListener::bind(&path).map_err(|err| Error::Listen(path.clone(), err))?;
+			info!("event=listen socket={}", quote(&path.to_string_lossy()));
+			let incoming = listener.incoming().then(accepted).filter_map(|stream| stream);
+			tokio::run(warp::serve(routes).serve_incoming(incoming));
+		},
+	}
+	Ok(())
+}
+
+/// Logs and skips a connection that failed to be accepted, pausing briefly instead of ending the server.
+fn accepted<S>(result: io::Result<S>) -> impl Future<Item = Option<S>, Error = io::Error> {
+	match result {
+		Ok(stream) => Either::A(future::ok(Some(stream))),
+		Err(err) => {
+			warn!("event=accept error={}", quote(&err.to_string()));
+			Either::B(Delay::new(Instant::now() + ACCEPT_BACKOFF).then(|_| Ok(None)))
+		},
+	}
+}
+
+/// Redirects every request to the same host and path over HTTPS on `port`.
+fn redirect_filter(port: u16) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+	let location = move |host: String, path: FullPath, query: Option<String>| {
+		// The host header may carry the HTTP port, including after an IPv6 address.
+		let name = match host.rfind(':') {
+			Some(index) if !host[index ..].contains(']') => &host[.. index],
+			_ => &host[..],
+		};
+		let authority = match port {
+			443 => name.to_string(),
+			port => format!("{}:{}", name, port),
+		};
+		let query = query.map(|query| format!("?{}", query)).unwrap_or_default();
+		Response::builder()
+			.header("location", format!("https://{}{}{}", authority, path.as_str(), query))
+			.status(StatusCode::MOVED_PERMANENTLY)
+			.body("".to_string())
+	};
+	warp::header::<String>("host").and(full())
+		.and(warp::query::raw()).map(move |host, path, query| location(host, path, Some(query)))
+		.or(warp::header::<String>("host").and(full()).map(move |host, path| location(host, path, None)))
+}
+
+const STYLE: &str = include_str!("../assets/style.css");
+const FAVICON: &[u8] = include_bytes!("../assets/favicon.png");
+
+/// Serves the static directory if one is given, caching challenge files by the content hash in their path.
+/// The default stylesheet and favicon are served when the directory lacks them.
+fn static_filter(dir: Option<PathBuf>) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+	let enabled = dir.is_some();
+	let dir = dir.unwrap_or_default();
+	let files = dir.join("files");
+	let unmodified = {
+		let files = files.clone();
+		path("files").and(param()).and(param()).and(end()).and(warp::header::optional("if-none-match"))
+			.and_then(move |hash: String, name: String, tags: Option<String>| {
+				let etag = format!("\"{}\"", hash);
+				let matched = tags.map(|tags| tags.split(',')
+					.map(|tag| tag.trim().trim_start_matches("W/"))
+					.any(|tag| tag == etag || tag == "*"))
+					.unwrap_or(false);
+				// Anything else falls through to the file itself, which is served or not found.
+				if matched && hash.chars().all(|c| c.is_ascii_hexdigit()) && !name.starts_with('.') &&
+					files.join(&hash).join(&name).is_file() {
+					Ok(Response::builder()
+						.header("etag", etag)
+						.status(StatusCode::NOT_MODIFIED)
+						.body("".to_string()))
+				} else {
+					Err(warp::reject::not_found())
+				}
+			})
+	};
+	// Files are never modified in place, since new contents get a new hash directory.
+	let hashed = path("files").and(peek()).and(warp::fs::dir(files))
+		.map(|peek: Peek, file| {
+			let hash = peek.segments().next().unwrap_or("").to_string();
+			with_header(with_header(file, "etag", format!("\"{}\"", hash)),
+				"cache-control", "public, max-age=31536000, immutable")
+		});
+	let defaults = path("style.css").and(end())
+		.map(|| Response::builder().header("content-type", "text/css; charset=utf-8").body(STYLE))
+		.or(path("favicon.png").and(end())
+			.map(|| Response::builder().header("content-type", "image/png").body(FAVICON)));
+	path("static")
+		.and(warp::get2())
+		.and(any().and_then(move || if enabled { Ok(()) } else { Err(warp::reject::not_found()) }).untuple_one()
+			.and(unmodified.or(hashed).or(warp::fs::dir(dir)))
+			.or(defaults))
+}
+
+#[cfg(test)]
+mod tests {
+	use native_tls::TlsConnector;
+	use postgres_native_tls::MakeTlsConnector;
+	use r2d2_postgres::PostgresConnectionManager;
+	use r2d2_postgres::r2d2::Pool;
+
+	use super::*;
+
+	/// A pool for a database that is never reached.
+	fn unreachable() -> ClientPool {
+		let manager = PostgresConnectionManager::new("postgres://scrap@127.0.0.1:1/scrap".parse().unwrap(),
+			MakeTlsConnector::new(TlsConnector::new().unwrap()));
+		Pool::builder().connection_timeout(Duration::from_millis(10)).build_unchecked(manager)
+	}
+
+	fn unavailable() -> Rejection {
+		match unreachable().get() {
+			Ok(_) => panic!("connected to an unreachable database"),
+			Err(err) => custom(err),
+		}
+	}
+
+	fn path(path: &str) -> FullPath {
+		warp::test::request().path(path).filter(&full()).unwrap()
+	}
+
+	fn body(response: reply::Response) -> String {
+		String::from_utf8(response.into_body().concat2().wait().unwrap().to_vec()).unwrap()
+	}
+
+	#[test]
+	fn status_follows_rejection() {
+		assert_eq!(status(&warp::reject::not_found()), StatusCode::NOT_FOUND);
+		let wrong_method = warp::test::request().method("POST").filter(&warp::get2()).unwrap_err();
+		assert_eq!(status(&wrong_method), StatusCode::METHOD_NOT_ALLOWED);
+		let malformed = warp::test::request().method("POST")
+			.header("content-type", "application/json")
+			.body("{")
+			.filter(&body::json::<HashMap<String, String>>())
+			.unwrap_err();
+		assert_eq!(status(&malformed), StatusCode::BAD_REQUEST);
+		assert_eq!(status(&unavailable()), StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(status(&custom(Error::Message("broken"))), StatusCode::INTERNAL_SERVER_ERROR);
+	}
+
+	#[test]
+	fn finish_draws_marked_responses_only() {
+		let site = Arc::new(RwLock::new(Site::default()));
+
+		let page = finish(path("/challenges"), "cookie".to_string(), None, site.clone(), unreachable(), error(unavailable()).unwrap());
+		assert_eq!(page.status(), StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(page.headers()["retry-after"], "5");
+		assert_eq!(page.headers()["content-type"], "text/html; charset=utf-8");
+		let page = body(page);
+		assert!(page.contains("<h1>Service Unavailable</h1>"));
+		assert!(page.contains("/logout"));
+
+		let json = finish(path("/api/v1/me"), String::new(), None, site.clone(), unreachable(), error(unavailable()).unwrap());
+		assert_eq!(json.status(), StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(json.headers()["content-type"], "application/json");
+		assert_eq!(body(json), r#"{"error":"The server is busy. Try again in a few seconds."}"#);
+
+		let passed = finish(path("/"), String::new(), None, site, unreachable(), reply::html("home"));
+		assert_eq!(passed.status(), StatusCode::OK);
+		assert_eq!(body(passed), "home");
+	}
+
+	#[test]
+	fn unmodified_only_for_existing_files() {
+		let dir = std::env::temp_dir().join(format!("scrap-static-{}", std::process::id()));
+		fs::create_dir_all(dir.join("files/0123abcd")).unwrap();
+		fs::write(dir.join("files/0123abcd/flag.txt"), "flag").unwrap();
+		let filter = static_filter(Some(dir.clone()));
+		let get = |path: &str, tag: Option<&str>| {
+			let request = warp::test::request().path(path);
+			match tag {
+				Some(tag) => request.header("if-none-match", tag),
+				None => request,
+			}.reply(&filter).status()
+		};
+
+		assert_eq!(get("/static/files/0123abcd/flag.txt", None), StatusCode::OK);
+		assert_eq!(get("/static/files/0123abcd/flag.txt", Some("\"0123abcd\"")), StatusCode::NOT_MODIFIED);
+		assert_eq!(get("/static/files/0123abcd/flag.txt", Some("\"4567cdef\"")), StatusCode::OK);
+		assert_eq!(get("/static/files/0123abcd/other.txt", None), StatusCode::NOT_FOUND);
+		assert_eq!(get("/static/files/0123abcd/other.txt", Some("\"0123abcd\"")), StatusCode::NOT_FOUND);
+		fs::remove_dir_all(dir).unwrap();
+	}
 }
@@ -0,0 +1,101 @@
+use std::sync::{Arc, RwLock};
+
+use r2d2_postgres::postgres::Transaction;
+use tera::{Context, Tera};
+
+use crate::Client;
+use crate::cache::Cache;
+use crate::error::Error;
+use crate::locale::{Locale, Text};
+use crate::template;
+
+/// The last loaded settings, keyed by the generation of the CTF and templates they were loaded at.
+pub type Site = Cache<Arc<Settings>>;
+
+/// What every page is drawn with, which only changes on import.
+#[derive(Default)]
+pub struct Settings {
+	pub title: String,
+	locale: Locale,
+	accept_language: bool,
+	templates: Option<Tera>,
+}
+
+impl Site {
+	/// Returns the cached settings, reloading them if the CTF or templates changed since.
+	pub fn load(site: &RwLock<Site>, client: &mut Client) -> Result<Arc<Settings>, Error> {
+		Cache::get(site, client, "site", |transaction| Ok(Arc::new(Settings::new(transaction)?)))
+	}
+
+	/// Returns the settings loaded last, or the defaults before any were.
+	pub fn settings(site: &RwLock<Site>) -> Arc<Settings> {
+		Cache::last(site).unwrap_or_default()
+	}
+}
+
+impl Settings {
+	fn new(client: &mut Transaction) -> Result<Self, Error> {
+		let ctf = client.query("SELECT title, locale, accept_language FROM scrap.ctf", &[])?;
+		let (title, locale, accept_language) = match ctf.first() {
+			Some(ctf) => (ctf.get("title"), Locale::new(ctf.get("locale")).unwrap_or_default(), ctf.get("accept_language")),
+			None => (String::new(), Locale::default(), false),
+		};
+		let templates = client.query("SELECT name, body FROM scrap.template", &[])?;
+		let templates = template::compile(&templates).map_err(Error::Template)?;
+		Ok(Settings { title, locale, accept_language, templates })
+	}
+
+	/// Picks the text for a request, from the CTF's locale or, if enabled, the request's Accept-Language header.
+	pub fn text(&self, language: &Option<String>) -> &'static Text {
+		match language {
+			Some(language) if self.accept_language => Locale::negotiate(language).unwrap_or(self.locale).text(),
+			_ => self.locale.text(),
+		}
+	}
+
+	/// Whether the repository has a template named `name`.
+	pub fn overrides(&self, name: &str) -> bool {
+		self.templates.as_ref()
+			.map(|templates| templates.get_template_names().any(|template| template == name))
+			.unwrap_or(false)
+	}
+
+	/// Renders the template `name`, or returns `None` if the repository does not have it.
+	pub fn render(&self, name: &str, context: &Context) -> Result<Option<String>, tera::Error> {
+		match &self.templates {
+			Some(templates) if self.overrides(name) => templates.render(name, context).map(Some),
+			_ => Ok(None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_present_templates_are_rendered() {
+		let mut templates = Tera::default();
+		templates.add_raw_template("layout.html", "{{ title }}: {{ content | safe }}").unwrap();
+		let mut context = Context::new();
+		context.insert("title", "CTF");
+		context.insert("content", "<p>Hi</p>");
+		assert_eq!(Settings::default().render("layout.html", &context).unwrap(), None);
+		let settings = Settings { title: "CTF".to_string(), templates: Some(templates), ..Settings::default() };
+		assert!(settings.overrides("layout.html"));
+		assert!(!settings.overrides("login.html"));
+		assert_eq!(settings.render("layout.html", &context).unwrap(), Some("CTF: <p>Hi</p>".to_string()));
+		assert_eq!(settings.render("login.html", &context).unwrap(), None);
+	}
+
+	#[test]
+	fn text_follows_accept_language_only_if_enabled() {
+		let language = Some("es-MX,es;q=0.9,en;q=0.5".to_string());
+		let fixed = Settings { locale: Locale::Fr, ..Settings::default() };
+		assert_eq!(fixed.text(&language).code, "fr");
+		let negotiated = Settings { locale: Locale::Fr, accept_language: true, ..Settings::default() };
+		assert_eq!(negotiated.text(&language).code, "es");
+		assert_eq!(negotiated.text(&Some("de".to_string())).code, "fr");
+		assert_eq!(negotiated.text(&None).code, "fr");
+	}
+}
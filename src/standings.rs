@@ -0,0 +1,35 @@
+use r2d2_postgres::postgres;
+use serde::Serialize;
+
+use crate::Client;
+
+#[derive(Serialize)]
+pub struct Standings {
+	standings: Vec<Standing>,
+}
+
+#[derive(Serialize)]
+pub struct Standing {
+	pos: i64,
+	team: String,
+	score: i32,
+}
+
+impl Standings {
+	pub fn new(client: &mut Client) -> Result<Self, postgres::Error> {
+		let standings = client.query("SELECT name, score, ROW_NUMBER()
+			OVER (ORDER BY score DESC, submit ASC) AS place FROM scrap.team ORDER BY score DESC, submit ASC", &[])?
+			.iter()
+			.map(|team| Standing {
+				pos: team.get("place"),
+				team: team.get("name"),
+				score: team.get("score"),
+			})
+			.collect();
+		Ok(Standings { standings })
+	}
+
+	pub fn empty() -> Self {
+		Standings { standings: Vec::new() }
+	}
+}
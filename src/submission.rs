@@ -0,0 +1,107 @@
+use chrono::DateTime;
+use chrono::offset::Utc;
+use log::info;
+use r2d2_postgres::postgres;
+
+use crate::Client;
+use crate::logger::quote;
+use crate::webhook::{Event, Webhooks};
+
+#[derive(PartialEq)]
+pub enum Outcome {
+	Incorrect,
+	Solved,
+	FirstBlood,
+}
+
+impl Outcome {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Outcome::Incorrect => "incorrect",
+			Outcome::Solved => "solved",
+			Outcome::FirstBlood => "first_blood",
+		}
+	}
+}
+
+pub fn open(client: &mut Client) -> Result<bool, postgres::Error> {
+	let now = Utc::now();
+	let ctf = &client.query("SELECT start, stop FROM scrap.ctf", &[])?[0];
+	Ok(!(ctf.try_get::<_, DateTime<Utc>>("start").map(|start| now < start).unwrap_or(false) ||
+		ctf.try_get::<_, DateTime<Utc>>("stop").map(|stop| now > stop).unwrap_or(false)))
+}
+
+pub fn attempt(client: &mut Client, team: i32, slug: &str, flag: &str) -> Result<Outcome, postgres::Error> {
+	let mut transaction = client.transaction()?;
+	let rows = transaction.execute("UPDATE scrap.team team
+		SET solves=update(team.solves, challenge.id), submit=NOW()
+		FROM scrap.challenge challenge
+		WHERE team.id=$1
+		AND slug=$2 AND flag=$3 AND challenge.enabled
+		AND NOT solved(team.solves, challenge.id)",
+		&[&team, &slug, &flag])? as i32;
+	if rows == 0 {
+		return Ok(Outcome::Incorrect);
+	}
+	let solves: i32 = transaction.query("UPDATE scrap.challenge
+		SET solves=solves+$2
+		WHERE slug=$1
+		RETURNING solves",
+		&[&slug, &rows])?[0].get("solves");
+	transaction.execute("INSERT INTO scrap.solve (team, challenge)
+		SELECT $1, id FROM scrap.challenge
+		WHERE slug=$2
+		ON CONFLICT DO NOTHING",
+		&[&team, &slug])?;
+	transaction.execute("UPDATE scrap.team team
+		SET score=score(team.id, team.solves)",
+		&[])?;
+	transaction.commit()?;
+	if solves == 1 {
+		Ok(Outcome::FirstBlood)
+	} else {
+		Ok(Outcome::Solved)
+	}
+}
+
+pub fn announce(webhooks: &Webhooks, outcome: &Outcome, team: i32, slug: &str) {
+	info!("event=submission id={} challenge={} outcome={}", team, quote(slug), outcome.name());
+	if *outcome == Outcome::Incorrect {
+		return;
+	}
+	webhooks.send(Event { kind: "solve", team, challenge: Some(slug.to_string()) });
+	if *outcome == Outcome::FirstBlood {
+		webhooks.send(Event { kind: "first_blood", team, challenge: Some(slug.to_string()) });
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{SystemTime, UNIX_EPOCH};
+
+	use super::*;
+	use crate::test_pool;
+
+	#[test]
+	fn only_enabled_challenges_accept_flags() {
+		let pool = match test_pool() {
+			Some(pool) => pool,
+			None => return,
+		};
+		let suffix = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos().to_string();
+		let name = format!("submission-{}", suffix);
+		let slug = format!("submission-{}", suffix);
+		let mut client = pool.get().unwrap();
+		let team: i32 = client.query("INSERT INTO scrap.team (name, email, hash) VALUES ($1, $1, '') RETURNING id",
+			&[&name]).unwrap()[0].get("id");
+		client.execute("INSERT INTO scrap.challenge (slug, title, author, description, flag, enabled, archived)
+			VALUES ($1, $1, '', '', 'flag', false, true)", &[&slug]).unwrap();
+
+		assert!(attempt(&mut client, team, &slug, "flag").unwrap() == Outcome::Incorrect);
+		client.execute("UPDATE scrap.challenge SET enabled=true, archived=false WHERE slug=$1", &[&slug]).unwrap();
+		assert!(attempt(&mut client, team, &slug, "flag").unwrap() == Outcome::FirstBlood);
+
+		client.execute("DELETE FROM scrap.team WHERE id=$1", &[&team]).unwrap();
+		client.execute("DELETE FROM scrap.challenge WHERE slug=$1", &[&slug]).unwrap();
+	}
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::Path;
+
+use r2d2_postgres::postgres::{self, Transaction};
+use r2d2_postgres::postgres::row::Row;
+use tera::Tera;
+
+use crate::error::Error;
+
+/// Template files from the repository's `templates` directory, which override the built-in markup.
+pub struct Templates {
+	templates: Vec<(String, String)>,
+}
+
+impl Templates {
+	/// Reads every file in `dir`, which may be missing, and checks that they parse together.
+	pub fn new(dir: &Path) -> Result<Self, Error> {
+		let mut templates = Vec::new();
+		if dir.is_dir() {
+			for entry in fs::read_dir(dir).map_err(|err| Error::Read(dir.to_path_buf(), err))? {
+				let path = entry.map_err(|err| Error::Read(dir.to_path_buf(), err))?.path();
+				let name = match path.file_name().and_then(|name| name.to_str()) {
+					Some(name) if path.is_file() => name.to_string(),
+					_ => continue,
+				};
+				let body = fs::read_to_string(&path)
+					.map_err(|err| Error::Read(path.clone(), err))?;
+				templates.push((name, body));
+			}
+		}
+		templates.sort();
+		Tera::default().add_raw_templates(templates.iter()
+			.map(|(name, body)| (name.as_str(), body.as_str()))
+			.collect::<Vec<_>>())
+			.map_err(Error::Template)?;
+		Ok(Templates { templates })
+	}
+
+	pub fn push(&self, transaction: &mut Transaction) -> Result<(), postgres::Error> {
+		transaction.simple_query("DELETE FROM scrap.template")?;
+		for (name, body) in &self.templates {
+			transaction.execute("INSERT INTO scrap.template (name, body) VALUES ($1, $2)", &[name, body])?;
+		}
+		Ok(())
+	}
+}
+
+/// Compiles rows of `scrap.template`, or returns `None` if there are none.
+pub fn compile(templates: &[Row]) -> Result<Option<Tera>, tera::Error> {
+	if templates.is_empty() {
+		return Ok(None);
+	}
+	let mut tera = Tera::default();
+	tera.add_raw_templates(templates.iter()
+		.map(|template| (template.get::<_, &str>("name"), template.get::<_, &str>("body")))
+		.collect::<Vec<_>>())?;
+	Ok(Some(tera))
+}
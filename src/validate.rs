@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::challenge::Challenge;
+use crate::ctf::Ctf;
+use crate::error::Error;
+use crate::template::Templates;
+
+/// A repository whose files all parsed without problems.
+pub struct Repository {
+	pub ctf: Ctf,
+	pub templates: Templates,
+	pub challenges: Vec<Challenge>,
+}
+
+/// Parses every file in `repo`, copying challenge files into `out` if given, and prints every problem found.
+pub fn parse(repo: &Path, out: Option<&Path>) -> Result<Repository, Error> {
+	let mut problems: Vec<String> = Vec::new();
+
+	let config = repo.join("ctf.toml");
+	let ctf = match Ctf::new(&config) {
+		Ok(ctf) => {
+			match (ctf.start(), ctf.stop()) {
+				(Some(start), Some(stop)) if start >= stop => problems.push(
+					format!("{}: start {} is not before stop {}", config.display(), start, stop)),
+				_ => (),
+			}
+			Some(ctf)
+		},
+		Err(e) => {
+			problems.push(e.to_string());
+			None
+		},
+	};
+
+	let templates = match Templates::new(&repo.join("templates")) {
+		Ok(templates) => Some(templates),
+		Err(e) => {
+			problems.push(e.to_string());
+			None
+		},
+	};
+
+	let mut slugs: BTreeMap<String, PathBuf> = BTreeMap::new();
+	let mut challenges = Vec::new();
+	for entry in fs::read_dir(repo).map_err(|err| Error::Read(repo.to_path_buf(), err))? {
+		let path = entry.map_err(|err| Error::Read(repo.to_path_buf(), err))?
+			.path().join("challenge.toml");
+		if !path.is_file() {
+			continue;
+		}
+		let challenge = match Challenge::new(&path, out) {
+			Ok(challenge) => challenge,
+			Err(e) => {
+				problems.push(e.to_string());
+				continue;
+			},
+		};
+		for href in challenge.unresolved() {
+			problems.push(format!("{}: link {} does not reference a file", path.display(), href));
+		}
+		if let Some(other) = slugs.insert(challenge.slug().to_string(), path.clone()) {
+			problems.push(format!("{}: slug {} is also used by {}", path.display(), challenge.slug(), other.display()));
+		}
+		challenges.push(challenge);
+	}
+
+	for problem in &problems {
+		println!("{}", problem);
+	}
+	match (ctf, templates) {
+		(Some(ctf), Some(templates)) if problems.is_empty() => Ok(Repository { ctf, templates, challenges }),
+		_ => Err(Error::Message("Repository is invalid.")),
+	}
+}
+
+pub fn run(repo: &Path) -> Result<(), Error> {
+	let repository = parse(repo, None)?;
+	println!("{}: {} challenges are valid.", repo.display(), repository.challenges.len());
+	Ok(())
+}